@@ -0,0 +1,246 @@
+use std::collections::VecDeque;
+use std::io::{self, Read, Write};
+use std::process::{Command, Stdio};
+
+use engine_2048::ai;
+use engine_2048::board::{Board, Cell, Direction, Position, MAX_SIDE};
+use engine_2048::rng::Rng;
+use engine_2048::rules::{Classic, Fibonacci, Rules, Threes};
+
+const HINT_DEPTH: u32 = 2;
+const USAGE: &str = "usage: cli-2048 [classic|fibonacci|threes] [--size ROWSxCOLUMNS] [--seed N]";
+
+struct Options {
+    rules: &'static dyn Rules,
+    rows: u8,
+    columns: u8,
+    seed: u64,
+}
+
+struct Game {
+    rules: &'static dyn Rules,
+    board: Board,
+    rng: Rng,
+    score: u64,
+    moves: u64,
+    history: Vec<(Board, Rng, u64, u64)>,
+    message: String,
+}
+
+enum Key {
+    Move(Direction),
+    Undo,
+    Hint,
+    Quit,
+    Other,
+}
+
+// Puts the terminal in unbuffered, no-echo mode for as long as it lives.
+// Without stty input falls back to lines, one key then Enter. Ctrl-C comes
+// in as a key rather than a signal, so quitting always goes through Drop and
+// leaves the shell as it was.
+struct RawMode(bool);
+
+impl RawMode {
+    fn enter() -> Self {
+        Self(stty(&["-icanon", "-echo", "-isig", "min", "1"]))
+    }
+}
+
+impl Drop for RawMode {
+    fn drop(&mut self) {
+        if self.0 {
+            stty(&["icanon", "echo", "isig"]);
+        }
+    }
+}
+
+// Reads whatever the terminal has ready at once. An arrow's ESC [ A arrives
+// in one piece, so an ESC with nothing after it is the Esc key itself and
+// doesn't wait for bytes that aren't coming.
+struct Keys<R> {
+    input: R,
+    pending: VecDeque<u8>,
+}
+
+fn stty(args: &[&str]) -> bool {
+    Command::new("stty")
+        .args(args)
+        .stderr(Stdio::null())
+        .status()
+        .is_ok_and(|status| status.success())
+}
+
+fn parse_options() -> Result<Options, String> {
+    let mut options = Options { rules: &Classic, rows: 6, columns: 6, seed: Rng::random_seed() };
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "classic" => options.rules = &Classic,
+            "fibonacci" => options.rules = &Fibonacci,
+            "threes" => options.rules = &Threes,
+            "--size" => {
+                let size = args.next().ok_or("--size needs a value")?;
+                let (rows, columns) = size.split_once('x').ok_or("size looks like 4x4")?;
+                options.rows = rows.parse().map_err(|_| "bad row count")?;
+                options.columns = columns.parse().map_err(|_| "bad column count")?;
+                if !(1..=MAX_SIDE).contains(&options.rows) || !(1..=MAX_SIDE).contains(&options.columns) {
+                    return Err(format!("sides go from 1 to {}", MAX_SIDE));
+                }
+            }
+            "--seed" => {
+                let seed = args.next().ok_or("--seed needs a value")?;
+                options.seed = seed.parse().map_err(|_| "bad seed")?;
+            }
+            "-h" | "--help" => return Err(USAGE.to_string()),
+            other => return Err(format!("unknown argument {}\n{}", other, USAGE)),
+        }
+    }
+    Ok(options)
+}
+
+impl Game {
+    fn new(options: &Options) -> Self {
+        let mut game = Self {
+            rules: options.rules,
+            board: Board::new(options.rows, options.columns),
+            rng: Rng::new(options.seed),
+            score: 0,
+            moves: 0,
+            history: Vec::new(),
+            message: format!("seed {}", options.seed),
+        };
+        game.board.spawn(game.rules, &mut game.rng);
+        game
+    }
+
+    fn play(&mut self, direction: Direction) {
+        let before = (self.board, self.rng, self.score, self.moves);
+        let result = self.board.apply(self.rules, direction);
+        if !result.moved {
+            self.message = format!("{:?} does nothing", direction);
+            return;
+        }
+        self.history.push(before);
+        self.score += result.score_delta;
+        self.moves += 1;
+        self.board.spawn(self.rules, &mut self.rng);
+        self.message = match result.score_delta {
+            0 => String::new(),
+            points => format!("+{}", points),
+        };
+    }
+
+    fn undo(&mut self) {
+        match self.history.pop() {
+            Some((board, rng, score, moves)) => {
+                (self.board, self.rng, self.score, self.moves) = (board, rng, score, moves);
+                self.message = "undone".to_string();
+            }
+            None => self.message = "nothing to undo".to_string(),
+        }
+    }
+
+    fn hint(&mut self) {
+        self.message = match ai::best_move(&self.board, self.rules, HINT_DEPTH) {
+            Some(direction) => format!("try {:?}", direction),
+            None => "no move left".to_string(),
+        };
+    }
+
+    fn render(&self, out: &mut impl Write) -> io::Result<()> {
+        let width = self.board.positions()
+            .map(|p| self.board[p].value().to_string().len())
+            .max()
+            .unwrap_or(1)
+            .max(4);
+        // Clear the screen and home the cursor; \r keeps lines straight in raw mode.
+        write!(out, "\x1b[2J\x1b[H")?;
+        write!(out, "score {}  moves {}\r\n\r\n", self.score, self.moves)?;
+        for row in 0..self.board.rows() {
+            for column in 0..self.board.columns() {
+                let text = match self.board[Position { row, column }] {
+                    Cell::Empty => ".".to_string(),
+                    Cell::Wall => "#".to_string(),
+                    Cell::Value(v) => v.to_string(),
+                };
+                write!(out, "{:>width$} ", text, width = width)?;
+            }
+            write!(out, "\r\n")?;
+        }
+        write!(out, "\r\n{}\r\n", self.message)?;
+        if self.board.dead(self.rules) {
+            write!(out, "game over, u to undo\r\n")?;
+        }
+        write!(out, "wasd or arrows move, u undo, h hint, q or Esc quit\r\n")?;
+        out.flush()
+    }
+}
+
+impl<R: Read> Keys<R> {
+    fn new(input: R) -> Self {
+        Self { input, pending: VecDeque::new() }
+    }
+
+    fn next(&mut self) -> io::Result<Option<Key>> {
+        if self.pending.is_empty() {
+            let mut chunk = [0u8; 16];
+            let read = self.input.read(&mut chunk)?;
+            self.pending.extend(&chunk[..read]);
+        }
+        let byte = match self.pending.pop_front() {
+            Some(byte) => byte,
+            None => return Ok(None),
+        };
+        let key = match byte {
+            b'w' => Key::Move(Direction::Up),
+            b'a' => Key::Move(Direction::Left),
+            b's' => Key::Move(Direction::Down),
+            b'd' => Key::Move(Direction::Right),
+            b'u' => Key::Undo,
+            b'h' | b'?' => Key::Hint,
+            // Ctrl-C and Ctrl-D too.
+            b'q' | 3 | 4 => Key::Quit,
+            0x1b if self.pending.front() == Some(&b'[') && self.pending.len() >= 2 => {
+                self.pending.pop_front();
+                match self.pending.pop_front() {
+                    Some(b'A') => Key::Move(Direction::Up),
+                    Some(b'B') => Key::Move(Direction::Down),
+                    Some(b'C') => Key::Move(Direction::Right),
+                    Some(b'D') => Key::Move(Direction::Left),
+                    _ => Key::Other,
+                }
+            }
+            0x1b => Key::Quit,
+            _ => Key::Other,
+        };
+        Ok(Some(key))
+    }
+}
+
+fn main() -> io::Result<()> {
+    let options = match parse_options() {
+        Ok(options) => options,
+        Err(message) => {
+            eprintln!("{}", message);
+            std::process::exit(2);
+        }
+    };
+    let mut game = Game::new(&options);
+    let _raw = RawMode::enter();
+    let stdin = io::stdin();
+    let mut keys = Keys::new(stdin.lock());
+    let mut out = io::stdout();
+    game.render(&mut out)?;
+    while let Some(key) = keys.next()? {
+        match key {
+            Key::Move(direction) => game.play(direction),
+            Key::Undo => game.undo(),
+            Key::Hint => game.hint(),
+            Key::Quit => break,
+            Key::Other => continue,
+        }
+        game.render(&mut out)?;
+    }
+    Ok(())
+}
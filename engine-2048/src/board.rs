@@ -0,0 +1,884 @@
+use alloc::vec::Vec;
+use core::ops::{Index, IndexMut};
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::wasm_bindgen;
+
+use crate::rng::Rng;
+use crate::rules::Rules;
+
+/// Which way a move pushes the tiles.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum Direction {
+    Up, Down, Left, Right
+}
+
+/// One square of a board. Walls never move and split a line in two.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Cell {
+    Empty,
+    Value(u64),
+    Wall,
+}
+
+/// A square by row and column, counted from the top left.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Position {
+    pub row: u8,
+    pub column: u8,
+}
+
+/// Every change to a board during play, in the order it happened. Boards only
+/// change by applying these, so a move's events say exactly what it did.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Event {
+    /// `from == to` for a tile that stayed put; it still ages a turn.
+    Slide { from: Position, to: Position },
+    Merge { from: Position, into: Position, value: u64 },
+    Spawn { position: Position, value: u64 },
+    Clear { position: Position },
+    Swap { a: Position, b: Position },
+}
+
+/// Per-tile history for display: how many merges went into it and how many
+/// turns it has sat still. Not part of board equality or serialization.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TileMeta {
+    pub merges: u32,
+    pub age: u32,
+    /// Unique among the tiles on a board and kept as the tile slides, so a
+    /// view can tell tiles apart across moves. 0 on an empty square.
+    pub id: u32,
+}
+
+/// Where one tile went during a move, by its [`TileMeta::id`]. `from` is
+/// `None` for a tile that appeared and `to` is `None` for one that was
+/// removed. A tile that merged goes to the square it merged into and is
+/// gone afterwards; the tile it merged with keeps its id.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Transition {
+    pub id: u32,
+    pub from: Option<Position>,
+    pub to: Option<Position>,
+}
+
+/// The longest side a board can have.
+pub const MAX_SIDE: u8 = 8;
+const CAPACITY: usize = MAX_SIDE as usize * MAX_SIDE as usize;
+
+/// A board of up to [`MAX_SIDE`] × [`MAX_SIDE`] squares; the default is the
+/// classic 6×6. Moves go through [`Board::apply`] or [`Board::slide`] and
+/// [`Board::spawn`].
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Debug, Clone, Copy)]
+pub struct Board {
+    rows: u8,
+    columns: u8,
+    // Laid out with a stride of MAX_SIDE whatever the size, so a position's
+    // index does not depend on the board. Squares outside stay empty.
+    cells: [Cell; CAPACITY],
+    meta: [TileMeta; CAPACITY],
+    next_id: u32,
+}
+
+/// What one move did, from [`Board::apply`]. Everything but `events` is a
+/// summary of them.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MoveResult {
+    pub events: Vec<Event>,
+    /// Whether any tile changed squares.
+    pub moved: bool,
+    pub merges: Vec<Merge>,
+    /// Where a tile appeared and its value, when the events include a spawn.
+    pub spawned: Option<(Position, u64)>,
+    /// Points scored by the move's merges.
+    pub score_delta: u64,
+    /// What the events did to each tile. Empty when the result was made
+    /// without the board it started from.
+    #[serde(default)]
+    pub transitions: Vec<Transition>,
+}
+
+/// One merge in a move: the square the merged tile ends up on and its value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Merge {
+    pub into: Position,
+    pub value: u64,
+}
+
+struct LineIteration {
+    head: Position,
+    direction: Direction,
+    rows: u8,
+    columns: u8,
+    ended: bool,
+}
+
+impl Direction {
+    pub const ALL: [Direction; 4] = [Direction::Up, Direction::Down, Direction::Left, Direction::Right];
+
+    fn opposite(&self) -> Self {
+        match self {
+            Direction::Up => Direction::Down,
+            Direction::Down => Direction::Up,
+            Direction::Left => Direction::Right,
+            Direction::Right => Direction::Left,
+        }
+    }
+
+    fn perpendicular_positive(&self) -> Self {
+        match self {
+            Direction::Up => Direction::Left,
+            Direction::Left => Direction::Down,
+            Direction::Down => Direction::Right,
+            Direction::Right => Direction::Up,
+        }
+    }
+}
+
+impl Cell {
+    /// The tile's value, or 0 for empty squares and walls.
+    pub fn value(&self) -> u64 {
+        match self {
+            Cell::Value(v) => *v,
+            _ => 0,
+        }
+    }
+}
+
+impl Event {
+    /// Points the event scores: the merged value for a merge, nothing otherwise.
+    pub fn score(&self) -> u64 {
+        match self {
+            Event::Merge { value, .. } => *value,
+            _ => 0,
+        }
+    }
+
+    /// Whether a tile changed squares, which is what makes a move count.
+    pub fn moves_tile(&self) -> bool {
+        match self {
+            Event::Slide { from, to } => from != to,
+            Event::Merge { .. } => true,
+            _ => false,
+        }
+    }
+}
+
+impl MoveResult {
+    /// Sums up a move's events; spawns may be among them.
+    pub fn from_events(events: Vec<Event>) -> Self {
+        Self {
+            moved: events.iter().any(Event::moves_tile),
+            merges: events.iter()
+                .filter_map(|event| match *event {
+                    Event::Merge { into, value, .. } => Some(Merge { into, value }),
+                    _ => None,
+                })
+                .collect(),
+            spawned: events.iter().find_map(|event| match *event {
+                Event::Spawn { position, value } => Some((position, value)),
+                _ => None,
+            }),
+            score_delta: events.iter().map(Event::score).sum(),
+            transitions: Vec::new(),
+            events,
+        }
+    }
+
+    /// Like [`MoveResult::from_events`], with the tiles' transitions taken
+    /// from `start`, the board before any of the events.
+    pub fn on_board(start: &Board, events: Vec<Event>) -> Self {
+        Self { transitions: start.transitions(&events), ..Self::from_events(events) }
+    }
+}
+
+impl Position {
+    /// Index used by [`Board::set`] and [`Board::empties`]: row-major with
+    /// a stride of [`MAX_SIDE`], the same on every board size.
+    pub fn position(self) -> usize {
+        (MAX_SIDE * self.row + self.column) as usize
+    }
+
+    fn neibouring_cell(self, pointing: Direction, rows: u8, columns: u8) -> Option<Position> {
+        match pointing {
+            Direction::Up => if self.row == 0 { None } else { Some(Position{row: self.row - 1, column: self.column}) },
+            Direction::Down => if self.row + 1 >= rows { None } else { Some(Position{row: self.row + 1, column: self.column}) },
+            Direction::Left => if self.column == 0 { None } else { Some(Position{row: self.row, column: self.column - 1}) },
+            Direction::Right => if self.column + 1 >= columns { None } else { Some(Position{row: self.row, column: self.column + 1}) },
+        }
+    }
+
+    /// Inverse of [`Position::position`].
+    pub fn from_index(index: usize) -> Self {
+        Self {
+            row: (index / MAX_SIDE as usize) as u8,
+            column: (index % MAX_SIDE as usize) as u8,
+        }
+    }
+}
+
+impl Index<Position> for Board {
+    type Output = Cell;
+
+    fn index(&self, i: Position) -> &Cell {
+        if !self.contains(i) {
+            panic!("Index out of bound!");
+        }
+        &self.cells[i.position()]
+    }
+}
+
+impl IndexMut<Position> for Board {
+    fn index_mut(&mut self, i: Position) -> &mut Cell {
+        if !self.contains(i) {
+            panic!("Index out of bound!");
+        }
+
+        &mut self.cells[i.position()]
+    }
+}
+
+impl Iterator for LineIteration {
+    type Item = Position;
+
+    fn next(&mut self) -> Option<Position> {
+        if self.ended {
+            return None;
+        }
+
+        let temp = self.head;
+        if let Some(next) = self.head.neibouring_cell(self.direction, self.rows, self.columns) {
+            self.head = next;
+        } else {
+            self.ended = true;
+        }
+
+        Some(temp)
+    }
+}
+
+impl LineIteration {
+    fn heads(direction: Direction, rows: u8, columns: u8) -> Self {
+        let (last_row, last_column) = (rows - 1, columns - 1);
+        let start = match direction {
+            Direction::Up => Position{row: 0, column: last_column},
+            Direction::Down => Position{row: last_row, column: 0},
+            Direction::Left => Position{row: 0, column: 0},
+            Direction::Right => Position{row: last_row, column: last_column},
+        };
+
+        Self {head: start, direction: direction.perpendicular_positive(), rows, columns, ended: false}
+    }
+}
+
+impl Default for Board {
+    fn default() -> Self {
+        Self::new(6, 6)
+    }
+}
+
+impl PartialEq for Board {
+    fn eq(&self, other: &Self) -> bool {
+        self.rows == other.rows && self.columns == other.columns && self.cells == other.cells
+    }
+}
+
+impl Eq for Board {}
+
+#[derive(Serialize, Deserialize)]
+struct SizedCells {
+    rows: u8,
+    columns: u8,
+    cells: Vec<Cell>,
+}
+
+// Saves from before boards had a size are a bare list of 36 cells.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum SavedCells {
+    Sized(SizedCells),
+    Classic(Vec<Cell>),
+}
+
+impl Serialize for Board {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let cells = self.positions().map(|p| self[p]).collect();
+        SizedCells { rows: self.rows, columns: self.columns, cells }.serialize(serializer)
+    }
+}
+
+// Only the cells round-trip; tile metadata starts fresh.
+impl<'de> Deserialize<'de> for Board {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let saved = match SavedCells::deserialize(deserializer)? {
+            SavedCells::Sized(saved) => saved,
+            SavedCells::Classic(cells) => SizedCells { rows: 6, columns: 6, cells },
+        };
+        if !(1..=MAX_SIDE).contains(&saved.rows) || !(1..=MAX_SIDE).contains(&saved.columns) {
+            return Err(de::Error::custom("board side out of range"));
+        }
+        let expected = saved.rows as usize * saved.columns as usize;
+        if saved.cells.len() != expected {
+            return Err(de::Error::invalid_length(saved.cells.len(), &"one cell per square"));
+        }
+        let mut board = Self::new(saved.rows, saved.columns);
+        for (p, cell) in board.positions().zip(saved.cells).collect::<Vec<_>>() {
+            board.set(p.position(), cell);
+        }
+        Ok(board)
+    }
+}
+
+impl Board {
+    /// An empty board of `rows` × `columns` squares.
+    ///
+    /// # Panics
+    ///
+    /// If either side is zero or longer than [`MAX_SIDE`].
+    pub fn new(rows: u8, columns: u8) -> Self {
+        assert!((1..=MAX_SIDE).contains(&rows) && (1..=MAX_SIDE).contains(&columns), "board side out of range");
+        Self { rows, columns, cells: [Cell::Empty; CAPACITY], meta: [TileMeta::default(); CAPACITY], next_id: 1 }
+    }
+
+    pub fn rows(&self) -> u8 {
+        self.rows
+    }
+
+    pub fn columns(&self) -> u8 {
+        self.columns
+    }
+
+    pub fn contains(&self, p: Position) -> bool {
+        p.row < self.rows && p.column < self.columns
+    }
+
+    /// Every square, row by row.
+    pub fn positions(&self) -> impl Iterator<Item = Position> {
+        let columns = self.columns;
+        (0..self.rows).flat_map(move |row| (0..columns).map(move |column| Position { row, column }))
+    }
+
+    /// The neighbouring square toward `direction`, if it is on the board.
+    pub fn neighbour(&self, p: Position, direction: Direction) -> Option<Position> {
+        p.neibouring_cell(direction, self.rows, self.columns)
+    }
+
+    pub fn meta(&self, p: Position) -> TileMeta {
+        self.meta[p.position()]
+    }
+
+    /// Indices of the empty squares, in order.
+    pub fn empties(&self) -> Vec<usize> {
+        self.positions().filter(|p| self[*p] == Cell::Empty).map(Position::position).collect()
+    }
+
+    /// Puts `cell` at `index`, for setting up a board rather than playing on it.
+    /// Anything but an empty square counts as a new tile and gets a fresh id.
+    pub fn set(&mut self, index: usize, cell: Cell) {
+        self.cells[index] = cell;
+        self.meta[index] = TileMeta::default();
+        if cell != Cell::Empty {
+            self.meta[index].id = self.next_id;
+            self.next_id += 1;
+        }
+    }
+
+    /// Follows each tile through `events`, which start from this board.
+    ///
+    /// ```
+    /// use engine_2048::board::{Board, Cell, Direction, Position};
+    /// use engine_2048::rules::Classic;
+    ///
+    /// let mut board = Board::new(1, 3);
+    /// board.set(1, Cell::Value(1));
+    /// let id = board.meta(Position { row: 0, column: 1 }).id;
+    /// let start = board;
+    /// let events = board.slide(&Classic, Direction::Left);
+    /// let transition = start.transitions(&events)[0];
+    /// assert_eq!(transition.id, id);
+    /// assert_eq!(transition.to, Some(Position { row: 0, column: 0 }));
+    /// assert_eq!(board.meta(Position { row: 0, column: 0 }).id, id);
+    /// ```
+    pub fn transitions(&self, events: &[Event]) -> Vec<Transition> {
+        let mut board = *self;
+        let mut transitions = Vec::new();
+        for event in events {
+            let tile = |board: &Board, from: Position, to: Option<Position>| Transition { id: board.meta(from).id, from: Some(from), to };
+            match *event {
+                Event::Slide { from, to } => transitions.push(tile(&board, from, Some(to))),
+                Event::Merge { from, into, .. } => transitions.push(tile(&board, from, Some(into))),
+                Event::Clear { position } => transitions.push(tile(&board, position, None)),
+                Event::Swap { a, b } => transitions.extend([tile(&board, a, Some(b)), tile(&board, b, Some(a))]),
+                Event::Spawn { .. } => {}
+            }
+            board.apply_event(*event);
+            if let Event::Spawn { position, .. } = *event {
+                transitions.push(Transition { id: board.meta(position).id, from: None, to: Some(position) });
+            }
+        }
+        transitions
+    }
+
+    /// Applies one event. Replaying a move's events on the board it started
+    /// from reproduces the board after it.
+    pub fn apply_event(&mut self, event: Event) {
+        match event {
+            Event::Slide { from, to } => {
+                self.move_tile(from, to);
+                if from != to {
+                    self.set(from.position(), Cell::Empty);
+                }
+            }
+            Event::Merge { from, into, value } => {
+                self[into] = Cell::Value(value);
+                self.meta[into.position()] = TileMeta {
+                    merges: self.meta[into.position()].merges + self.meta[from.position()].merges + 1,
+                    age: 0,
+                    id: self.meta[into.position()].id,
+                };
+                self.set(from.position(), Cell::Empty);
+            }
+            Event::Spawn { position, value } => self.set(position.position(), Cell::Value(value)),
+            Event::Clear { position } => self.set(position.position(), Cell::Empty),
+            Event::Swap { a, b } => {
+                self.cells.swap(a.position(), b.position());
+                self.meta.swap(a.position(), b.position());
+            }
+        }
+    }
+
+    /// Draws from `rng` but leaves the board alone; apply the event to place
+    /// the tile. `None` when the board is full.
+    pub fn spawn_event(&self, rules: &dyn Rules, rng: &mut Rng) -> Option<Event> {
+        let empties = self.empties();
+        if empties.is_empty() {
+            return None;
+        }
+
+        let position = Position::from_index(empties[rng.below(empties.len())]);
+        let value = rules.spawn_value(rng);
+        Some(Event::Spawn { position, value })
+    }
+
+    /// Places a tile from `rules` on a random empty square.
+    pub fn spawn(&mut self, rules: &dyn Rules, rng: &mut Rng) -> Option<Event> {
+        let event = self.spawn_event(rules, rng)?;
+        self.apply_event(event);
+        Some(event)
+    }
+
+    /// The largest tile value, 0 on an empty board.
+    pub fn highest(&self) -> u64 {
+        self.cells.iter().map(Cell::value).max().unwrap_or(0)
+    }
+
+    /// True when no square is empty and no neighbours can merge.
+    pub fn dead(&self, rules: &dyn Rules) -> bool {
+        for p in self.positions() {
+            if self[p] == Cell::Empty {
+                return false;
+            }
+            for direction in Direction::ALL {
+                if let Some(j) = self.neighbour(p, direction) {
+                    if self.mergeable(rules, p, j) {
+                        return false
+                    }
+                }
+            }
+        }
+
+        true
+    }
+
+    fn mergeable(&self, rules: &dyn Rules, x: Position, y: Position) -> bool {
+        match (self[x], self[y]) {
+            (Cell::Value(a), Cell::Value(b)) => rules.mergeable(a, b),
+            _ => false,
+        }
+    }
+
+    fn aggregate(&mut self, rules: &dyn Rules, head: Position, direction: Direction, events: &mut Vec<Event>) {
+        let line: Vec<Position> = LineIteration {head, direction: direction.opposite(), rows: self.rows, columns: self.columns, ended: false}.collect();
+        let segments: Vec<Vec<Position>> = line.split(|p| self[*p] == Cell::Wall).map(|s| s.to_vec()).collect();
+        for segment in segments {
+            self.aggregate_segment(rules, &segment, events);
+        }
+    }
+
+    fn emit(&mut self, event: Event, events: &mut Vec<Event>) {
+        self.apply_event(event);
+        events.push(event);
+    }
+
+    // Compacts the segment toward its front: each tile either merges into
+    // the last square filled or takes the next one. A square a merge
+    // produced is marked and takes nothing more this move, so 2 2 2 2 ends
+    // up 4 4 and never 8.
+    //
+    // Tiles are read front to back and only ever land at or before the one
+    // being read, so clearing each source as it leaves empties the tail.
+    fn aggregate_segment(&mut self, rules: &dyn Rules, segment: &[Position], events: &mut Vec<Event>) {
+        let mut filled: usize = 0;
+        let mut merged = [false; MAX_SIDE as usize];
+
+        for &p in segment {
+            if self[p] == Cell::Empty {
+                continue;
+            }
+            if let Some(last) = filled.checked_sub(1) {
+                let into = segment[last];
+                if !merged[last] && self.mergeable(rules, into, p) {
+                    let value = rules.merge(self[into].value(), self[p].value());
+                    self.emit(Event::Merge {from: p, into, value}, events);
+                    merged[last] = true;
+                    continue;
+                }
+            }
+            self.emit(Event::Slide {from: p, to: segment[filled]}, events);
+            filled += 1;
+        }
+    }
+
+    fn move_tile(&mut self, from: Position, to: Position) {
+        self[to] = self[from];
+        let mut meta = self.meta[from.position()];
+        meta.age = if from == to { meta.age + 1 } else { 0 };
+        self.meta[to.position()] = meta;
+    }
+
+    /// Slides every tile toward `direction`, merging pairs the rules allow,
+    /// and returns what happened.
+    ///
+    /// ```
+    /// use engine_2048::board::{Board, Cell, Direction, Position};
+    /// use engine_2048::rules::Classic;
+    ///
+    /// let mut board = Board::default();
+    /// board.set(0, Cell::Value(1));
+    /// board.set(1, Cell::Value(1));
+    /// let events = board.slide(&Classic, Direction::Right);
+    ///
+    /// assert_eq!(board[Position { row: 0, column: 5 }], Cell::Value(2));
+    /// assert_eq!(events.iter().map(|e| e.score()).sum::<u64>(), 2);
+    /// ```
+    pub fn slide(&mut self, rules: &dyn Rules, direction: Direction) -> Vec<Event> {
+        let mut events = Vec::new();
+        let heads = LineIteration::heads(direction, self.rows, self.columns);
+        for head in heads {
+            self.aggregate(rules, head, direction, &mut events);
+        }
+        events
+    }
+
+    /// The squares each line of a move toward `direction` starts from, in
+    /// the order [`Board::slide`] works through them.
+    pub fn line_heads(&self, direction: Direction) -> Vec<Position> {
+        LineIteration::heads(direction, self.rows, self.columns).collect()
+    }
+
+    /// One line of [`Board::slide`], the one starting at `head`, so a move
+    /// can be stepped through line by line.
+    ///
+    /// ```
+    /// use engine_2048::board::{Board, Cell, Direction};
+    /// use engine_2048::rules::Classic;
+    ///
+    /// let mut board = Board::new(4, 4);
+    /// board.set(0, Cell::Value(1));
+    /// board.set(1, Cell::Value(1));
+    /// let mut stepped = board;
+    /// let events: Vec<_> = board.line_heads(Direction::Left).into_iter()
+    ///     .flat_map(|head| stepped.slide_line(&Classic, head, Direction::Left))
+    ///     .collect();
+    /// assert_eq!(events, board.slide(&Classic, Direction::Left));
+    /// assert_eq!(stepped, board);
+    /// ```
+    pub fn slide_line(&mut self, rules: &dyn Rules, head: Position, direction: Direction) -> Vec<Event> {
+        let mut events = Vec::new();
+        self.aggregate(rules, head, direction, &mut events);
+        events
+    }
+
+    /// A whole move for callers that do not need the events one by one:
+    /// slides toward `direction`, without spawning.
+    ///
+    /// ```
+    /// use engine_2048::board::{Board, Cell, Direction};
+    /// use engine_2048::rules::Classic;
+    ///
+    /// let mut board = Board::new(4, 4);
+    /// board.set(0, Cell::Value(2));
+    /// board.set(1, Cell::Value(2));
+    /// let result = board.apply(&Classic, Direction::Left);
+    /// assert!(result.moved);
+    /// assert_eq!(result.score_delta, 4);
+    /// assert_eq!(result.merges.len(), 1);
+    /// assert!(!board.apply(&Classic, Direction::Left).moved);
+    /// ```
+    pub fn apply(&mut self, rules: &dyn Rules, direction: Direction) -> MoveResult {
+        let start = *self;
+        MoveResult::on_board(&start, self.slide(rules, direction))
+    }
+
+    /// The events [`Board::slide`] would emit, leaving this board unchanged.
+    pub fn slide_events(&self, rules: &dyn Rules, direction: Direction) -> Vec<Event> {
+        let mut scratch = *self;
+        scratch.slide(rules, direction)
+    }
+}
+
+// Properties checked over seeded random boards. proptest and quickcheck are
+// not dependencies, so boards come from the crate's own Rng and a failure
+// names the seed that produced it.
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+    use crate::rules::{Classic, Fibonacci, Threes};
+
+    const CASES: u64 = 2000;
+    const SIZES: [(u8, u8); 4] = [(6, 6), (4, 4), (8, 8), (3, 7)];
+
+    fn rule_sets() -> [(&'static dyn Rules, &'static [u64]); 3] {
+        [
+            (&Classic, &[1, 2, 4, 8, 16, 32, 64]),
+            (&Fibonacci, &[1, 2, 3, 5, 8, 13, 21]),
+            (&Threes, &[1, 2, 3, 6, 12, 24]),
+        ]
+    }
+
+    // Mostly tiles from a small palette so merges are common, some empty
+    // squares and, if asked for, a few walls.
+    fn random_board(seed: u64, values: &[u64], walls: bool) -> Board {
+        let mut rng = Rng::new(seed);
+        let (rows, columns) = SIZES[seed as usize % SIZES.len()];
+        let mut board = Board::new(rows, columns);
+        for p in board.positions().collect::<Vec<_>>() {
+            let cell = match rng.below(20) {
+                0..=6 => Cell::Empty,
+                7 if walls => Cell::Wall,
+                _ => Cell::Value(values[rng.below(values.len())]),
+            };
+            board.set(p.position(), cell);
+        }
+        board
+    }
+
+    fn mass(board: &Board) -> u64 {
+        board.cells.iter().map(Cell::value).sum()
+    }
+
+    fn moves(board: &Board, rules: &dyn Rules) -> bool {
+        Direction::ALL.iter().any(|&direction| {
+            let mut next = *board;
+            next.slide(rules, direction);
+            next != *board
+        })
+    }
+
+    #[test]
+    fn slides_conserve_mass_and_spawns_add_their_value() {
+        for (rules, values) in rule_sets() {
+            for seed in 0..CASES {
+                let mut board = random_board(seed, values, true);
+                let before = mass(&board);
+                let direction = Direction::ALL[seed as usize % 4];
+                board.slide(rules, direction);
+                assert_eq!(mass(&board), before, "seed {} sliding {:?}", seed, direction);
+
+                let mut rng = Rng::new(seed);
+                match board.spawn(rules, &mut rng) {
+                    Some(Event::Spawn { value, .. }) => assert_eq!(mass(&board), before + value, "seed {}", seed),
+                    other => assert!(other.is_none() && board.empties().is_empty(), "seed {}", seed),
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn merged_tiles_take_no_further_part_in_the_move() {
+        for (rules, values) in rule_sets() {
+            for seed in 0..CASES {
+                let board = random_board(seed, values, true);
+                for direction in Direction::ALL {
+                    let events = board.slide_events(rules, direction);
+                    for (i, event) in events.iter().enumerate() {
+                        let into = match event {
+                            Event::Merge { into, .. } => *into,
+                            _ => continue,
+                        };
+                        let touched = events[i + 1..].iter().any(|later| match later {
+                            Event::Slide { from, to } => *from == into || *to == into,
+                            Event::Merge { from, into: target, .. } => *from == into || *target == into,
+                            _ => false,
+                        });
+                        assert!(!touched, "seed {} sliding {:?}: {:?} merged again", seed, direction, into);
+                    }
+                }
+            }
+        }
+    }
+
+    // One row slid left, written front first; 0 is an empty square.
+    fn slid_left(rules: &dyn Rules, line: &[u64]) -> Vec<u64> {
+        let mut board = Board::new(1, line.len() as u8);
+        for (i, &value) in line.iter().enumerate() {
+            board.set(i, if value == 0 { Cell::Empty } else { Cell::Value(value) });
+        }
+        board.slide(rules, Direction::Left);
+        board.positions().map(|p| board[p].value()).collect()
+    }
+
+    #[test]
+    fn a_merged_tile_does_not_merge_again_in_the_same_move() {
+        let cases: [(&[u64], &[u64]); 8] = [
+            (&[2, 2, 2, 2], &[4, 4, 0, 0]),
+            (&[4, 2, 2], &[4, 4, 0]),
+            (&[2, 2, 4], &[4, 4, 0]),
+            (&[2, 2, 2], &[4, 2, 0]),
+            (&[4, 4, 8], &[8, 8, 0]),
+            (&[8, 4, 4, 8], &[8, 8, 8, 0]),
+            (&[2, 0, 2, 2, 0, 2], &[4, 4, 0, 0, 0, 0]),
+            (&[1, 1, 2, 4], &[2, 2, 4, 0]),
+        ];
+        for (line, expected) in cases {
+            assert_eq!(slid_left(&Classic, line), expected, "sliding {:?}", line);
+        }
+        // Fibonacci pairs 1 2 into 3 and 1 1 into 2; neither result merges on.
+        assert_eq!(slid_left(&Fibonacci, &[1, 1, 3]), [2, 3, 0]);
+        assert_eq!(slid_left(&Fibonacci, &[1, 2, 3]), [3, 3, 0]);
+    }
+
+    #[test]
+    fn walls_split_a_line_into_separate_compactions() {
+        let mut board = Board::new(1, 5);
+        for (i, cell) in [Cell::Value(2), Cell::Wall, Cell::Value(2), Cell::Value(2), Cell::Value(2)].into_iter().enumerate() {
+            board.set(i, cell);
+        }
+        board.slide(&Classic, Direction::Left);
+        let cells: Vec<Cell> = board.positions().map(|p| board[p]).collect();
+        assert_eq!(cells, [Cell::Value(2), Cell::Wall, Cell::Value(4), Cell::Value(2), Cell::Empty]);
+    }
+
+    #[test]
+    fn a_move_that_slides_nothing_changes_nothing() {
+        for (rules, values) in rule_sets() {
+            for seed in 0..CASES {
+                let board = random_board(seed, values, true);
+                for direction in Direction::ALL {
+                    let mut next = board;
+                    let events = next.slide(rules, direction);
+                    if !events.iter().any(Event::moves_tile) {
+                        assert_eq!(next, board, "seed {} sliding {:?}", seed, direction);
+                        let mut again = next;
+                        again.slide(rules, direction);
+                        assert_eq!(again, next, "seed {} sliding {:?} twice", seed, direction);
+                    }
+                }
+            }
+        }
+    }
+
+    // A full board where no tile can merge with the one above or to its left,
+    // so it is dead, unless the palette runs out of safe values.
+    fn stuck_board(seed: u64, values: &[u64], rules: &dyn Rules) -> Board {
+        let mut rng = Rng::new(seed);
+        let (rows, columns) = SIZES[seed as usize % SIZES.len()];
+        let mut board = Board::new(rows, columns);
+        for p in board.positions().collect::<Vec<_>>() {
+            let neighbours: Vec<u64> = [Direction::Up, Direction::Left].iter()
+                .filter_map(|&direction| board.neighbour(p, direction))
+                .map(|q| board[q].value())
+                .collect();
+            let start = rng.below(values.len());
+            let value = (0..values.len())
+                .map(|offset| values[(start + offset) % values.len()])
+                .find(|&v| neighbours.iter().all(|&n| !rules.mergeable(v, n)))
+                .unwrap_or(values[start]);
+            board.set(p.position(), Cell::Value(value));
+        }
+        board
+    }
+
+    // Without walls any board with a tile and an empty square has a move, so
+    // dead() should match brute force exactly. With walls an empty square can
+    // be boxed in, so only "dead means stuck" holds.
+    #[test]
+    fn dead_agrees_with_trying_every_move() {
+        for (rules, values) in rule_sets() {
+            for seed in 0..CASES {
+                let mut board = match seed % 2 {
+                    0 => random_board(seed, values, false),
+                    _ => stuck_board(seed, values, rules),
+                };
+                if board.highest() == 0 {
+                    continue;
+                }
+                // Knock one tile out, or copy a neighbour over it, so boards
+                // one move away from dead turn up too.
+                if seed % 3 == 0 {
+                    let mut rng = Rng::new(!seed);
+                    let positions: Vec<Position> = board.positions().collect();
+                    let p = positions[rng.below(positions.len())];
+                    let cell = match board.neighbour(p, Direction::Right) {
+                        Some(q) if seed % 2 == 1 => board[q],
+                        _ => Cell::Empty,
+                    };
+                    board.set(p.position(), cell);
+                }
+                assert_eq!(board.dead(rules), !moves(&board, rules), "seed {}", seed);
+
+                let walled = random_board(seed, values, true);
+                if walled.dead(rules) {
+                    assert!(!moves(&walled, rules), "seed {} with walls", seed);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn tile_ids_stay_unique_and_follow_their_tiles() {
+        for (rules, values) in rule_sets() {
+            for seed in 0..CASES {
+                let mut board = random_board(seed, values, true);
+                let mut rng = Rng::new(seed);
+                for direction in Direction::ALL {
+                    let start = board;
+                    let mut events = board.slide(rules, direction);
+                    events.extend(board.spawn(rules, &mut rng));
+                    for transition in start.transitions(&events) {
+                        if let Some(from) = transition.from {
+                            assert_eq!(start.meta(from).id, transition.id, "seed {}", seed);
+                        }
+                    }
+                    let ids: Vec<u32> = board.positions().filter(|p| board[*p] != Cell::Empty).map(|p| board.meta(p).id).collect();
+                    assert!(ids.iter().all(|id| *id != 0), "seed {}", seed);
+                    let mut unique = ids.clone();
+                    unique.sort_unstable();
+                    unique.dedup();
+                    assert_eq!(unique.len(), ids.len(), "seed {} sliding {:?}", seed, direction);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn boards_round_trip_and_old_saves_still_load() {
+        for (_, values) in rule_sets() {
+            for seed in 0..CASES / 10 {
+                let board = random_board(seed, values, true);
+                let saved = serde_json::to_string(&board).unwrap();
+                assert_eq!(serde_json::from_str::<Board>(&saved).unwrap(), board, "seed {}", seed);
+            }
+        }
+
+        let mut old = vec![Cell::Empty; 36];
+        old[7] = Cell::Value(4);
+        let board: Board = serde_json::from_str(&serde_json::to_string(&old).unwrap()).unwrap();
+        assert_eq!((board.rows(), board.columns()), (6, 6));
+        assert_eq!(board[Position { row: 1, column: 1 }], Cell::Value(4));
+        assert!(serde_json::from_str::<Board>("[]").is_err());
+    }
+}
@@ -0,0 +1,115 @@
+//! Bindings for embedding the engine in JavaScript, behind the `wasm`
+//! feature. Boards cross the boundary as opaque handles and come out as JSON.
+//!
+//! The crate stays an rlib so it still builds without `std`; ask for a
+//! cdylib when building for JavaScript:
+//!
+//! ```sh
+//! cargo rustc -p engine-2048 --release --features wasm --target wasm32-unknown-unknown --crate-type cdylib
+//! wasm-bindgen --target web target/wasm32-unknown-unknown/release/engine_2048.wasm --out-dir pkg
+//! ```
+//!
+//! ```js
+//! import init, { Board, Direction, Rng, RuleSet, apply_move } from "./engine_2048.js";
+//!
+//! await init();
+//! const board = new Board(4, 4);
+//! const rng = new Rng(42n);
+//! board.spawn(RuleSet.Classic, rng);
+//! const result = JSON.parse(apply_move(board, Direction.Left, RuleSet.Classic, rng));
+//! localStorage.setItem("board", board.toJSON());
+//! ```
+
+// The glue wasm-bindgen 0.2.79 generates for exported impls trips this.
+#![allow(clippy::unused_unit)]
+
+use alloc::string::{String, ToString};
+use wasm_bindgen::prelude::*;
+
+use crate::board::{Board, Direction, Event, MoveResult, MAX_SIDE};
+use crate::rng::Rng;
+use crate::rules::{Classic, Fibonacci, Rules, Threes};
+
+/// The rule sets in [`crate::rules`], by name for JavaScript.
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RuleSet {
+    Classic,
+    Fibonacci,
+    Threes,
+}
+
+impl RuleSet {
+    fn rules(self) -> &'static dyn Rules {
+        match self {
+            RuleSet::Classic => &Classic,
+            RuleSet::Fibonacci => &Fibonacci,
+            RuleSet::Threes => &Threes,
+        }
+    }
+}
+
+#[wasm_bindgen]
+impl Board {
+    /// Throws instead of panicking when a side is out of range.
+    #[wasm_bindgen(constructor)]
+    pub fn create(rows: u8, columns: u8) -> Result<Board, JsValue> {
+        if !(1..=MAX_SIDE).contains(&rows) || !(1..=MAX_SIDE).contains(&columns) {
+            return Err(JsValue::from_str("board side out of range"));
+        }
+        Ok(Board::new(rows, columns))
+    }
+
+    #[wasm_bindgen(getter, js_name = rows)]
+    pub fn js_rows(&self) -> u8 {
+        self.rows()
+    }
+
+    #[wasm_bindgen(getter, js_name = columns)]
+    pub fn js_columns(&self) -> u8 {
+        self.columns()
+    }
+
+    #[wasm_bindgen(js_name = isDead)]
+    pub fn js_dead(&self, rules: RuleSet) -> bool {
+        self.dead(rules.rules())
+    }
+
+    /// Places one tile; false when the board is full.
+    #[wasm_bindgen(js_name = spawn)]
+    pub fn js_spawn(&mut self, rules: RuleSet, rng: &mut Rng) -> bool {
+        self.spawn(rules.rules(), rng).is_some()
+    }
+
+    /// The same `{ rows, columns, cells }` JSON the web game saves.
+    #[wasm_bindgen(js_name = toJSON)]
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).unwrap_or_default()
+    }
+
+    #[wasm_bindgen(js_name = fromJSON)]
+    pub fn from_json(json: &str) -> Result<Board, JsValue> {
+        serde_json::from_str(json).map_err(|e| JsValue::from_str(&e.to_string()))
+    }
+}
+
+#[wasm_bindgen]
+impl Rng {
+    #[wasm_bindgen(constructor)]
+    pub fn create(seed: u64) -> Rng {
+        Rng::new(seed)
+    }
+}
+
+/// Plays one move and, if anything moved, spawns from `rng`. Returns the
+/// move's [`crate::board::MoveResult`] as JSON.
+#[wasm_bindgen]
+pub fn apply_move(board: &mut Board, direction: Direction, rules: RuleSet, rng: &mut Rng) -> String {
+    let rules = rules.rules();
+    let start = *board;
+    let mut events = board.slide(rules, direction);
+    if events.iter().any(Event::moves_tile) {
+        events.extend(board.spawn(rules, rng));
+    }
+    serde_json::to_string(&MoveResult::on_board(&start, events)).unwrap_or_default()
+}
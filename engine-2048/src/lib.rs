@@ -0,0 +1,38 @@
+//! The game engine behind yew-2048, usable without the web front end: boards
+//! and their moves, the rule sets, the seeded spawn generator and the AI.
+//!
+//! Builds without `std` (it needs `alloc`) when default features are off;
+//! the `std` feature only adds [`rng::Rng::random_seed`]. The `wasm` feature
+//! adds JavaScript bindings in `js`.
+//!
+//! ```
+//! use engine_2048::board::Board;
+//! use engine_2048::rng::Rng;
+//! use engine_2048::rules::Classic;
+//!
+//! let mut rng = Rng::new(2048);
+//! let mut board = Board::new(4, 4);
+//! board.spawn(&Classic, &mut rng);
+//! board.spawn(&Classic, &mut rng);
+//!
+//! for _ in 0..10 {
+//!     let direction = engine_2048::ai::best_move(&board, &Classic, 1).unwrap();
+//!     if board.apply(&Classic, direction).moved {
+//!         board.spawn(&Classic, &mut rng);
+//!     }
+//! }
+//!
+//! let saved = serde_json::to_string(&board).unwrap();
+//! assert_eq!(serde_json::from_str::<Board>(&saved).unwrap(), board);
+//! ```
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+pub mod ai;
+pub mod board;
+#[cfg(feature = "wasm")]
+pub mod js;
+pub mod rng;
+pub mod rules;
@@ -0,0 +1,50 @@
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::wasm_bindgen;
+
+/// The spawn generator. Saving it alongside a board resumes the exact same
+/// sequence of tiles.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    /// A seed from the OS or browser entropy source. Needs the `std` feature.
+    #[cfg(feature = "std")]
+    pub fn random_seed() -> u64 {
+        let mut buffer = [0u8; 8];
+        getrandom::getrandom(&mut buffer).unwrap();
+        u64::from_le_bytes(buffer)
+    }
+
+    /// Where the sequence has got to; a fresh generator's state is its seed.
+    pub fn state(&self) -> u64 {
+        self.state
+    }
+
+    /// SplitMix64, so a seed alone reproduces the whole spawn sequence.
+    ///
+    /// ```
+    /// use engine_2048::rng::Rng;
+    ///
+    /// assert_eq!(Rng::new(7).next_u64(), Rng::new(7).next_u64());
+    /// ```
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e3779b97f4a7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^ (z >> 31)
+    }
+
+    /// A value in `0..n`; `n` must not be zero.
+    pub fn below(&mut self, n: usize) -> usize {
+        (self.next_u64() % n as u64) as usize
+    }
+}
@@ -0,0 +1,107 @@
+use crate::rng::Rng;
+
+/// A rule set: which tiles merge, into what, and what new tiles look like.
+pub trait Rules {
+    fn mergeable(&self, a: u64, b: u64) -> bool;
+    fn merge(&self, a: u64, b: u64) -> u64;
+    /// Whether a board whose highest tile is `highest` has been won.
+    fn wins(&self, highest: u64) -> bool;
+
+    fn spawn_value(&self, _rng: &mut Rng) -> u64 {
+        1
+    }
+
+    /// Every value [`Rules::spawn_value`] can return, for searching over spawns.
+    fn spawn_outcomes(&self) -> &'static [u64] {
+        &[1]
+    }
+}
+
+/// Equal tiles merge into their sum, starting from 1; 2048 wins. Tiles stop
+/// merging at 2^63, the last sum a `u64` holds.
+///
+/// ```
+/// use engine_2048::rules::{Classic, Rules};
+///
+/// assert!(Classic.mergeable(4, 4));
+/// assert_eq!(Classic.merge(4, 4), 8);
+/// assert!(!Classic.mergeable(1 << 63, 1 << 63));
+/// ```
+pub struct Classic;
+
+/// Neighbouring Fibonacci numbers merge; 2584 wins.
+pub struct Fibonacci;
+
+/// 1 and 2 make 3, then equal multiples of three merge; spawns 1, 2 or 3.
+pub struct Threes;
+
+impl Rules for Classic {
+    fn mergeable(&self, a: u64, b: u64) -> bool {
+        a == b && a.checked_add(b).is_some()
+    }
+
+    fn merge(&self, a: u64, b: u64) -> u64 {
+        a + b
+    }
+
+    fn wins(&self, highest: u64) -> bool {
+        highest >= 2048
+    }
+}
+
+impl Rules for Fibonacci {
+    fn mergeable(&self, a: u64, b: u64) -> bool {
+        let (low, high) = if a <= b { (a, b) } else { (b, a) };
+        if low == 1 && high == 1 {
+            return true;
+        }
+
+        let (mut x, mut y) = (1u64, 2u64);
+        while x < high {
+            if x == low && y == high {
+                return true;
+            }
+            let next = x.saturating_add(y);
+            x = y;
+            y = next;
+        }
+        false
+    }
+
+    fn merge(&self, a: u64, b: u64) -> u64 {
+        a + b
+    }
+
+    fn wins(&self, highest: u64) -> bool {
+        highest >= 2584
+    }
+}
+
+impl Rules for Threes {
+    fn mergeable(&self, a: u64, b: u64) -> bool {
+        match (a, b) {
+            (1, 2) | (2, 1) => true,
+            _ => a == b && a >= 3 && a.is_multiple_of(3) && a.checked_add(b).is_some(),
+        }
+    }
+
+    fn merge(&self, a: u64, b: u64) -> u64 {
+        a + b
+    }
+
+    fn wins(&self, highest: u64) -> bool {
+        highest >= 6144
+    }
+
+    fn spawn_value(&self, rng: &mut Rng) -> u64 {
+        match rng.below(10) {
+            0 => 3,
+            n if n.is_multiple_of(2) => 2,
+            _ => 1,
+        }
+    }
+
+    fn spawn_outcomes(&self) -> &'static [u64] {
+        &[1, 2, 3]
+    }
+}
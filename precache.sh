@@ -0,0 +1,15 @@
+#!/bin/sh
+# Trunk post-build hook: lists the staged files for sw.js to cache. The
+# version is a checksum of them, so every build that changes anything gets
+# a fresh cache.
+cd "$TRUNK_STAGING_DIR" || exit 1
+files=$(find . -type f ! -name precache.js | sed 's|^\./||' | sort)
+version=$(cat $files | cksum | cut -d ' ' -f 1)
+{
+    printf 'const VERSION = "yew-2048-%s";\n' "$version"
+    printf 'const ASSETS = [\n    "./",\n'
+    for file in $files; do
+        printf '    "%s",\n' "$file"
+    done
+    printf '];\n'
+} > precache.js
@@ -0,0 +1,121 @@
+use std::collections::BTreeSet;
+
+use gloo::storage::{LocalStorage, Storage};
+use serde::{Deserialize, Serialize};
+use yew::{function_component, html, Html, Properties};
+
+use crate::storage::Namespace;
+
+const ACHIEVEMENTS_KEY: &str = "achievements";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum Achievement {
+    First128,
+    First2048,
+    WinWithoutUndo,
+    Merges1000,
+    Survive500,
+}
+
+pub struct Progress {
+    pub highest_tile: u64,
+    pub won: bool,
+    pub undo_used: bool,
+    pub merges: u64,
+    pub moves: u64,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Achievements {
+    unlocked: BTreeSet<Achievement>,
+    lifetime_merges: u64,
+    #[serde(skip)]
+    namespace: Namespace,
+}
+
+impl Achievement {
+    pub const ALL: [Achievement; 5] = [
+        Achievement::First128,
+        Achievement::First2048,
+        Achievement::WinWithoutUndo,
+        Achievement::Merges1000,
+        Achievement::Survive500,
+    ];
+
+    pub fn title(&self) -> &'static str {
+        match self {
+            Achievement::First128 => "初见128",
+            Achievement::First2048 => "2048达成",
+            Achievement::WinWithoutUndo => "落子无悔",
+            Achievement::Merges1000 => "千次合并",
+            Achievement::Survive500 => "苟过500步",
+        }
+    }
+
+    fn reached(&self, progress: &Progress, lifetime_merges: u64) -> bool {
+        match self {
+            Achievement::First128 => progress.highest_tile >= 128,
+            Achievement::First2048 => progress.highest_tile >= 2048,
+            Achievement::WinWithoutUndo => progress.won && !progress.undo_used,
+            Achievement::Merges1000 => lifetime_merges >= 1000,
+            Achievement::Survive500 => progress.moves >= 500,
+        }
+    }
+}
+
+impl Achievements {
+    pub fn load(namespace: &Namespace) -> Self {
+        let achievements: Self = LocalStorage::get(namespace.key(ACHIEVEMENTS_KEY)).unwrap_or_default();
+        Self { namespace: namespace.clone(), ..achievements }
+    }
+
+    fn save(&self) {
+        if let Err(e) = LocalStorage::set(self.namespace.key(ACHIEVEMENTS_KEY), self) {
+            log::warn!("Failed to save achievements: {:?}", e);
+        }
+    }
+
+    pub fn evaluate(&mut self, progress: &Progress) -> Vec<Achievement> {
+        self.lifetime_merges += progress.merges;
+
+        let newly: Vec<Achievement> = Achievement::ALL.iter()
+            .filter(|a| !self.unlocked.contains(a) && a.reached(progress, self.lifetime_merges))
+            .copied()
+            .collect();
+        self.unlocked.extend(newly.iter().copied());
+
+        if progress.merges > 0 || !newly.is_empty() {
+            self.save();
+        }
+        newly
+    }
+
+    pub fn view(&self) -> Html {
+        html! {
+            <table class="stats">
+            { Achievement::ALL.iter().map(|a| {
+                let mark = if self.unlocked.contains(a) { "✔" } else { "" };
+                html! {
+                    <tr><td>{ a.title() }</td><td>{ mark }</td></tr>
+                }
+            }).collect::<Html>() }
+            </table>
+        }
+    }
+}
+
+#[derive(Properties, PartialEq)]
+pub struct ToastProps {
+    pub message: String,
+}
+
+#[function_component(Toast)]
+pub fn toast(props: &ToastProps) -> Html {
+    html! {
+        <div class="toast">{ &props.message }</div>
+    }
+}
+
+pub fn unlocked(achievement: Achievement) -> String {
+    format!("解锁成就：{}", achievement.title())
+}
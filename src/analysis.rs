@@ -0,0 +1,123 @@
+use yew::{html, Html};
+
+use crate::ai;
+use crate::board::{Board, Direction, Event};
+use crate::rng::Rng;
+use crate::rules::Rules;
+use crate::settings::Action;
+
+// Deeper than the live blunder check; one search costs tens of milliseconds,
+// so only one runs per idle tick.
+const DEPTH: u32 = 3;
+const LINE_MOVES: usize = 6;
+const CAPACITY: usize = 8;
+
+pub struct Blunder {
+    pub move_number: u64,
+    pub played: Direction,
+    // Expectimax value given up by the move that was played.
+    pub loss: f64,
+    pub continuation: Continuation,
+}
+
+pub struct Continuation {
+    board: Board,
+    rng: Rng,
+    pub line: Vec<Direction>,
+    pub points: u64,
+    pub done: bool,
+}
+
+impl Continuation {
+    fn new(board: Board, move_number: u64) -> Self {
+        Self { board, rng: Rng::new(move_number), line: Vec::new(), points: 0, done: false }
+    }
+
+    // One search, then the move and a spawn from a fixed sequence.
+    fn step(&mut self, rules: &dyn Rules) {
+        let direction = match ai::best_move(&self.board, rules, DEPTH) {
+            Some(direction) => direction,
+            None => {
+                self.done = true;
+                return;
+            }
+        };
+        self.points += self.board.slide(rules, direction).iter().map(Event::score).sum::<u64>();
+        self.board.spawn(rules, &mut self.rng);
+        self.line.push(direction);
+        self.done = self.line.len() >= LINE_MOVES;
+    }
+}
+
+// Blunders recorded during play, worst first, with best continuations worked
+// out while the player is away from the board.
+#[derive(Default)]
+pub struct Analysis {
+    blunders: Vec<Blunder>,
+}
+
+impl Analysis {
+    pub fn record(&mut self, move_number: u64, board: Board, played: Direction, loss: f64) {
+        self.blunders.push(Blunder {
+            move_number,
+            played,
+            loss,
+            continuation: Continuation::new(board, move_number),
+        });
+        self.blunders.sort_by(|a, b| b.loss.total_cmp(&a.loss));
+        self.blunders.truncate(CAPACITY);
+    }
+
+    // Moves taken back by undo are forgotten with their blunders.
+    pub fn forget_after(&mut self, moves: u64) {
+        self.blunders.retain(|blunder| blunder.move_number <= moves);
+    }
+
+    pub fn clear(&mut self) {
+        self.blunders.clear();
+    }
+
+    pub fn pending(&self) -> bool {
+        self.blunders.iter().any(|blunder| !blunder.continuation.done)
+    }
+
+    pub fn advance(&mut self, rules: &dyn Rules) -> bool {
+        match self.blunders.iter_mut().find(|blunder| !blunder.continuation.done) {
+            Some(blunder) => {
+                blunder.continuation.step(rules);
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn view(&self) -> Html {
+        if self.blunders.is_empty() {
+            return html! {};
+        }
+        let mut blunders: Vec<&Blunder> = self.blunders.iter().collect();
+        blunders.sort_by_key(|blunder| blunder.move_number);
+        html! {
+            <div class="analysis">
+            <p>{ "失误复盘" }</p>
+            <table class="stats">
+            <tr><th>{ "第几步" }</th><th>{ "走了" }</th><th>{ "该走" }</th><th>{ "之后几步" }</th><th>{ "得分" }</th></tr>
+            { blunders.iter().map(|blunder| {
+                let continuation = &blunder.continuation;
+                let line: Vec<&str> = continuation.line.iter().map(|d| Action::Move(*d).label()).collect();
+                let line = line.join("");
+                html! {
+                    <tr>
+                    <td>{ blunder.move_number }</td>
+                    <td>{ Action::Move(blunder.played).label() }</td>
+                    <td>{ continuation.line.first().map(|d| Action::Move(*d).label()).unwrap_or("…") }</td>
+                    <td>{ if continuation.done { line } else { format!("{}…", line) } }</td>
+                    <td>{ continuation.points }</td>
+                    </tr>
+                }
+            }).collect::<Html>() }
+            </table>
+            </div>
+        }
+    }
+}
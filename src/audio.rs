@@ -0,0 +1,104 @@
+use wasm_bindgen::JsValue;
+use web_sys::{AudioContext, OscillatorType};
+
+use crate::board::MoveResult;
+
+const MAX_GAIN: f32 = 0.4;
+const SILENT_GAIN: f32 = 0.001;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Sound {
+    Slide,
+    Merge(u64),
+    Spawn,
+    Win,
+    GameOver,
+}
+
+impl Sound {
+    // (frequency in Hz, start offset in seconds, length in seconds)
+    fn notes(&self) -> Vec<(f32, f64, f64)> {
+        match self {
+            Sound::Slide => vec![(180.0, 0.0, 0.05)],
+            // Bigger merges climb a semitone per doubling.
+            Sound::Merge(value) => {
+                let tier = (64 - value.leading_zeros()).min(24) as f32;
+                vec![(330.0 * 2f32.powf(tier / 12.0), 0.0, 0.09)]
+            }
+            Sound::Spawn => vec![(660.0, 0.06, 0.04)],
+            Sound::Win => vec![(523.25, 0.0, 0.12), (659.25, 0.12, 0.12), (783.99, 0.24, 0.3)],
+            Sound::GameOver => vec![(392.0, 0.0, 0.2), (311.13, 0.2, 0.2), (261.63, 0.4, 0.4)],
+        }
+    }
+
+    fn wave(&self) -> OscillatorType {
+        match self {
+            Sound::Slide => OscillatorType::Triangle,
+            Sound::Merge(_) | Sound::Spawn => OscillatorType::Sine,
+            Sound::Win | Sound::GameOver => OscillatorType::Triangle,
+        }
+    }
+}
+
+// What a move sounds like: the biggest merge drowns out the slide it came with.
+pub fn for_move(result: &MoveResult) -> Vec<Sound> {
+    let mut sounds = Vec::new();
+    match result.merges.iter().map(|merge| merge.value).max() {
+        Some(value) => sounds.push(Sound::Merge(value)),
+        None if result.moved => sounds.push(Sound::Slide),
+        None => {}
+    }
+    if result.spawned.is_some() {
+        sounds.push(Sound::Spawn);
+    }
+    sounds
+}
+
+#[derive(Default)]
+pub struct Audio {
+    context: Option<AudioContext>,
+}
+
+impl Audio {
+    // Browsers only start an AudioContext after a user gesture, and every
+    // sound follows one, so it is created on first use.
+    fn context(&mut self) -> Option<&AudioContext> {
+        if self.context.is_none() {
+            self.context = AudioContext::new()
+                .map_err(|e| log::warn!("Failed to create audio context: {:?}", e))
+                .ok();
+        }
+        self.context.as_ref()
+    }
+
+    pub fn play(&mut self, sound: Sound, volume: u32) {
+        if volume == 0 {
+            return;
+        }
+        let gain = volume.min(100) as f32 / 100.0 * MAX_GAIN;
+        let context = match self.context() {
+            Some(context) => context,
+            None => return,
+        };
+        for (frequency, offset, length) in sound.notes() {
+            if let Err(e) = note(context, sound.wave(), frequency, offset, length, gain) {
+                log::warn!("Failed to play sound: {:?}", e);
+            }
+        }
+    }
+}
+
+fn note(context: &AudioContext, wave: OscillatorType, frequency: f32, offset: f64, length: f64, gain: f32) -> Result<(), JsValue> {
+    let start = context.current_time() + offset;
+    let oscillator = context.create_oscillator()?;
+    oscillator.set_type(wave);
+    oscillator.frequency().set_value(frequency);
+    let envelope = context.create_gain()?;
+    envelope.gain().set_value_at_time(gain, start)?;
+    envelope.gain().exponential_ramp_to_value_at_time(SILENT_GAIN, start + length)?;
+    oscillator.connect_with_audio_node(&envelope)?;
+    envelope.connect_with_audio_node(&context.destination())?;
+    oscillator.start_with_when(start)?;
+    oscillator.stop_with_when(start + length)?;
+    Ok(())
+}
@@ -0,0 +1,48 @@
+use gloo::storage::{LocalStorage, Storage};
+
+use crate::handoff::Handoff;
+use crate::storage::Namespace;
+
+const AUTOSAVE_KEY: &str = "autosave";
+// How long after a change that isn't a move, like a power-up or an undo, it
+// gets written at the latest.
+pub const DELAY_MS: u32 = 5000;
+
+// The game in progress, stored as a handoff without the code around it; the
+// expiry is never checked. Each write remembers what it wrote, so saving an
+// unchanged game costs a serialization and nothing more.
+#[derive(Debug)]
+pub struct Autosave {
+    namespace: Namespace,
+    written: Option<String>,
+}
+
+impl Autosave {
+    pub fn new(namespace: &Namespace) -> Self {
+        Self { namespace: namespace.clone(), written: None }
+    }
+
+    pub fn load(&self) -> Option<Handoff> {
+        LocalStorage::get(self.namespace.key(AUTOSAVE_KEY)).ok()
+    }
+
+    pub fn save(&mut self, game: &Handoff) {
+        let json = match serde_json::to_string(game) {
+            Ok(json) => json,
+            Err(e) => return log::warn!("Failed to serialize the autosave: {:?}", e),
+        };
+        if self.written.as_ref() == Some(&json) {
+            return;
+        }
+        // Stored as the value itself rather than a string of it, like every other key.
+        match LocalStorage::set(self.namespace.key(AUTOSAVE_KEY), game) {
+            Ok(()) => self.written = Some(json),
+            Err(e) => log::warn!("Failed to autosave: {:?}", e),
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.written = None;
+        LocalStorage::delete(self.namespace.key(AUTOSAVE_KEY));
+    }
+}
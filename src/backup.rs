@@ -0,0 +1,87 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use yew::{html, Callback, Html, MouseEvent};
+
+use crate::storage::{self, PREFIX};
+use crate::summary;
+
+// Bumped whenever a stored value changes shape in a way older builds can't read.
+const VERSION: u32 = 1;
+
+// Everything this app keeps in localStorage, every instance, as the raw
+// stored text under its key minus the prefix.
+#[derive(Debug, Serialize, Deserialize)]
+struct Backup {
+    version: u32,
+    entries: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ImportError {
+    Malformed,
+    Version(u32),
+    Storage,
+}
+
+impl ImportError {
+    pub fn message(&self) -> String {
+        match self {
+            ImportError::Malformed => "备份文件看不懂".to_string(),
+            ImportError::Version(version) => format!("备份是第{}版的，这个版本只认到第{}版，先更新一下", version, VERSION),
+            ImportError::Storage => "写不进本地存储，可能满了".to_string(),
+        }
+    }
+}
+
+pub fn export() {
+    let backup = Backup {
+        version: VERSION,
+        entries: storage::local_entries().into_iter()
+            .map(|(key, value)| (key.trim_start_matches(PREFIX).to_string(), value))
+            .collect(),
+    };
+    match serde_json::to_string(&backup) {
+        Ok(json) => {
+            let url = format!("data:application/json;charset=utf-8,{}", js_sys::encode_uri_component(&json));
+            summary::save(&url, "yew-2048-backup.json");
+        }
+        Err(e) => log::warn!("Failed to export data: {:?}", e),
+    }
+}
+
+// Checks the whole file before writing anything. Keys it carries replace
+// mine; keys it doesn't are left alone. Returns how many were written.
+pub fn import(text: &str) -> Result<usize, ImportError> {
+    let backup: Backup = serde_json::from_str(text).map_err(|_| ImportError::Malformed)?;
+    if backup.version == 0 || backup.version > VERSION {
+        return Err(ImportError::Version(backup.version));
+    }
+    let readable = backup.entries.iter()
+        .all(|(key, value)| !key.is_empty() && serde_json::from_str::<serde_json::Value>(value).is_ok());
+    if !readable {
+        return Err(ImportError::Malformed);
+    }
+    for (key, value) in &backup.entries {
+        if !storage::store(&format!("{}{}", PREFIX, key), value) {
+            return Err(ImportError::Storage);
+        }
+    }
+    Ok(backup.entries.len())
+}
+
+pub fn view(result: Option<&Result<usize, ImportError>>, onexport: Callback<MouseEvent>, onchange: Callback<yew::events::Event>) -> Html {
+    html! {
+        <div class="backup">
+        <h3>{ "备份" }</h3>
+        <p>{ "设置、高分、成就、统计和存下的对局打包成一个文件，换个浏览器导进去就行。" }</p>
+        <button onclick={onexport}>{ "导出数据" }</button>
+        <label>{ "导入数据：" }<input type="file" accept="application/json,.json" {onchange} /></label>
+        { match result {
+            Some(Ok(count)) => html! { <p>{ format!("导进了{}项", count) }</p> },
+            Some(Err(e)) => html! { <p class="warning">{ e.message() }</p> },
+            None => html! {},
+        } }
+        </div>
+    }
+}
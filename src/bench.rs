@@ -0,0 +1,101 @@
+use yew::{html, Callback, Html, MouseEvent};
+
+use crate::ai::{Expectimax, MonteCarlo, Solver};
+use crate::board::{Board, Direction};
+use crate::perf::now;
+use crate::rng::Rng;
+use crate::rules::{Classic, Rules};
+
+const BUDGET_MS: f64 = 200.0;
+const SEED: u64 = 306;
+const BOARDS: usize = 16;
+const WARM_UP_MOVES: usize = 40;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct BenchResult {
+    pub name: &'static str,
+    pub runs: u64,
+    pub ms: f64,
+}
+
+impl BenchResult {
+    pub fn per_second(&self) -> f64 {
+        if self.ms > 0.0 { self.runs as f64 * 1000.0 / self.ms } else { 0.0 }
+    }
+}
+
+// Mid-game positions from fixed seeds, so runs compare across builds.
+fn boards() -> Vec<Board> {
+    let mut rng = Rng::new(SEED);
+    (0..BOARDS).map(|_| {
+        let mut board = Board::default();
+        board.spawn(&Classic, &mut rng);
+        for _ in 0..WARM_UP_MOVES {
+            board.slide(&Classic, Direction::ALL[rng.below(4)]);
+            board.spawn(&Classic, &mut rng);
+        }
+        board
+    }).collect()
+}
+
+fn measure(name: &'static str, mut run: impl FnMut(u64)) -> BenchResult {
+    let start = now();
+    let mut runs = 0;
+    while now() - start < BUDGET_MS {
+        run(runs);
+        runs += 1;
+    }
+    let result = BenchResult { name, runs, ms: now() - start };
+    log::info!("bench {}: {:.0}/s ({} runs in {:.1}ms)", result.name, result.per_second(), result.runs, result.ms);
+    result
+}
+
+// Blocks for about a second; only reachable from the debug menu.
+pub fn run() -> Vec<BenchResult> {
+    let boards = boards();
+    let rules: &dyn Rules = &Classic;
+    let pick = |i: u64| boards[i as usize % boards.len()];
+    let mut rng = Rng::new(SEED);
+    vec![
+        measure("slide", |i| {
+            let mut board = pick(i);
+            board.slide(rules, Direction::ALL[i as usize % 4]);
+        }),
+        measure("slide_events", |i| {
+            pick(i).slide_events(rules, Direction::ALL[i as usize % 4]);
+        }),
+        measure("spawn", |i| {
+            pick(i).spawn(rules, &mut rng);
+        }),
+        measure("dead", |i| {
+            pick(i).dead(rules);
+        }),
+        measure("expectimax(2)", |i| {
+            Expectimax { depth: 2 }.best_move(&pick(i), rules);
+        }),
+        measure("monte_carlo(6×10)", |i| {
+            MonteCarlo { rollouts: 6, moves: 10 }.best_move(&pick(i), rules);
+        }),
+    ]
+}
+
+pub fn view(results: &[BenchResult], onrun: Callback<MouseEvent>) -> Html {
+    html! {
+        <div class="bench">
+        <button onclick={onrun}>{ "跑分" }</button>
+        if !results.is_empty() {
+            <table class="stats">
+            <tr><th>{ "项目" }</th><th>{ "每秒" }</th><th>{ "次数" }</th><th>{ "毫秒" }</th></tr>
+            { results.iter().map(|result| html! {
+                <tr>
+                <td>{ result.name }</td>
+                <td>{ format!("{:.0}", result.per_second()) }</td>
+                <td>{ result.runs }</td>
+                <td>{ format!("{:.1}", result.ms) }</td>
+                </tr>
+            }).collect::<Html>() }
+            </table>
+        }
+        </div>
+    }
+}
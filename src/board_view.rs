@@ -0,0 +1,31 @@
+use yew::{function_component, html, Html, Properties};
+
+use crate::board::{Board, Cell, Position};
+use crate::theme::{self, DisplayMode, Palette};
+
+#[derive(Properties, PartialEq)]
+pub struct BoardViewProps {
+    pub board: Board,
+    pub palette: Palette,
+    pub display: DisplayMode,
+}
+
+#[function_component(BoardView)]
+pub fn board_view(props: &BoardViewProps) -> Html {
+    html! {
+        <table class="preview" role="grid" aria-readonly="true">
+        { (0..props.board.rows()).map(|row| html! {
+            <tr role="row">
+            { (0..props.board.columns()).map(|column| {
+                let cell = props.board[Position{row, column}];
+                html! {
+                    <td role="gridcell" class={theme::tile_class(props.palette, cell)}>
+                    { match cell { Cell::Value(v) => props.display.format(v), _ => "".to_string() } }
+                    </td>
+                }
+            }).collect::<Html>() }
+            </tr>
+        }).collect::<Html>() }
+        </table>
+    }
+}
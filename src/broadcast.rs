@@ -0,0 +1,200 @@
+use std::collections::VecDeque;
+
+use gloo::timers::callback::Interval;
+use yew::{html, Component, Context, Html};
+
+use crate::board::MoveResult;
+use crate::board_view::BoardView;
+use crate::flags;
+use crate::game_view_2048::{GameProps, GameState};
+use crate::net::{self, NetEvent, Relay, State, Wire};
+use crate::race::Report;
+use crate::theme::{DisplayMode, Palette};
+
+// Spectators run this far behind, so moves arrive evenly even when the
+// network doesn't.
+const LATENCY_MS: f64 = 600.0;
+const PLAYBACK_MS: u32 = 50;
+
+pub enum Msg {
+    Net(NetEvent),
+    Report(Box<Report>),
+    Turn(Box<MoveResult>),
+}
+
+// ?broadcast: an ordinary game whose every move goes out to the room named
+// in the spectate link.
+pub struct Broadcast {
+    relay: Relay,
+    number: u64,
+    state: Option<State>,
+}
+
+impl Component for Broadcast {
+    type Message = Msg;
+    type Properties = ();
+
+    fn create(ctx: &Context<Self>) -> Self {
+        Self {
+            relay: Relay::join(net::join_code(), ctx.link().callback(Msg::Net)),
+            number: 0,
+            state: None,
+        }
+    }
+
+    fn update(&mut self, _ctx: &Context<Self>, msg: Self::Message) -> bool {
+        match msg {
+            Msg::Net(event) => {
+                self.relay.handle(&event);
+                // Late spectators start from the current board.
+                if let NetEvent::Message(wire) = &event {
+                    if let Wire::Hello { .. } = **wire {
+                        self.relay.send(&Wire::Sync { seed: None, state: self.state });
+                    }
+                }
+                true
+            }
+            Msg::Report(report) => {
+                self.state = Some(State { board: report.board, score: report.score, over: report.over });
+                false
+            }
+            Msg::Turn(result) => {
+                self.number += 1;
+                if let Some(state) = self.state {
+                    self.relay.send(&Wire::Frame { number: self.number, result: *result, state });
+                }
+                false
+            }
+        }
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        let link = ctx.link();
+        let props = GameProps {
+            instance: "broadcast".to_string(),
+            onreport: link.callback(|report| Msg::Report(Box::new(report))),
+            onturn: link.callback(|result| Msg::Turn(Box::new(result))),
+            ..GameProps::default()
+        };
+        html! {
+            <div class="broadcast">
+            <p>{ format!("直播中 · {} · 观战链接：{}", self.relay.status.text(), spectate_url(self.relay.room())) }</p>
+            <GameState ..props />
+            </div>
+        }
+    }
+}
+
+fn spectate_url(room: &str) -> String {
+    let location = web_sys::window().map(|w| w.location());
+    let base = location
+        .and_then(|l| Some(format!("{}{}", l.origin().ok()?, l.pathname().ok()?)))
+        .unwrap_or_default();
+    format!("{}?spectate={}", base, room)
+}
+
+pub enum SpectateMsg {
+    Net(NetEvent),
+    Play,
+}
+
+// ?spectate=CODE: someone else's board, replayed from the frames they send.
+pub struct Spectate {
+    relay: Option<Relay>,
+    buffer: VecDeque<(f64, u64, MoveResult, State)>,
+    latest: u64,
+    shown: Option<State>,
+    last: Option<MoveResult>,
+    _playback: Interval,
+}
+
+impl Spectate {
+    fn last_move(&self) -> String {
+        let display = DisplayMode::default();
+        match &self.last {
+            Some(result) if !result.merges.is_empty() => {
+                let merged: Vec<String> = result.merges.iter().map(|merge| display.format(merge.value)).collect();
+                format!("合成了{}", merged.join("、"))
+            }
+            Some(_) => "没有合成".to_string(),
+            None => "".to_string(),
+        }
+    }
+}
+
+impl Component for Spectate {
+    type Message = SpectateMsg;
+    type Properties = ();
+
+    fn create(ctx: &Context<Self>) -> Self {
+        let room = flags::query_param("spectate").and_then(|code| net::normalize_code(&code));
+        let link = ctx.link().clone();
+        Self {
+            relay: room.map(|room| Relay::join(room, ctx.link().callback(SpectateMsg::Net))),
+            buffer: VecDeque::new(),
+            latest: 0,
+            shown: None,
+            last: None,
+            _playback: Interval::new(PLAYBACK_MS, move || link.send_message(SpectateMsg::Play)),
+        }
+    }
+
+    fn update(&mut self, _ctx: &Context<Self>, msg: Self::Message) -> bool {
+        match msg {
+            SpectateMsg::Net(event) => {
+                let relay = match &mut self.relay {
+                    Some(relay) => relay,
+                    None => return false,
+                };
+                relay.handle(&event);
+                match event {
+                    NetEvent::Open => relay.send(&Wire::Hello { seed: None, state: None }),
+                    NetEvent::Message(wire) => match *wire {
+                        Wire::Sync { state: Some(state), .. } => {
+                            self.buffer.clear();
+                            self.shown = Some(state);
+                        }
+                        Wire::Frame { number, result, state } if number != self.latest => {
+                            self.latest = number;
+                            self.buffer.push_back((js_sys::Date::now(), number, result, state));
+                        }
+                        _ => {}
+                    },
+                    NetEvent::Closed | NetEvent::Retry => {}
+                }
+                true
+            }
+            SpectateMsg::Play => {
+                let now = js_sys::Date::now();
+                let mut played = false;
+                while self.buffer.front().is_some_and(|(arrived, ..)| now - arrived >= LATENCY_MS) {
+                    if let Some((_, _, result, state)) = self.buffer.pop_front() {
+                        self.shown = Some(state);
+                        self.last = Some(result);
+                        played = true;
+                    }
+                }
+                played
+            }
+        }
+    }
+
+    fn view(&self, _ctx: &Context<Self>) -> Html {
+        let relay = match &self.relay {
+            Some(relay) => relay,
+            None => return html! { <p>{ "观战链接不对" }</p> },
+        };
+        html! {
+            <div class="spectate">
+            <p>{ format!("观战 {} · {}", relay.room(), relay.status.text()) }</p>
+            if let Some(state) = &self.shown {
+                <p class="score">{ format!("分数：{}{}", state.score, if state.over { "，结束" } else { "" }) }</p>
+                <BoardView board={state.board} palette={Palette::default()} display={DisplayMode::default()} />
+                <p>{ self.last_move() }</p>
+            } else {
+                <p>{ "等主播走第一步…" }</p>
+            }
+            </div>
+        }
+    }
+}
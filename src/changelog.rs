@@ -0,0 +1,70 @@
+use gloo::storage::{LocalStorage, Storage};
+use serde::Deserialize;
+use yew::{html, Callback, Html, MouseEvent};
+
+use crate::storage::Namespace;
+
+const LAST_SEEN_KEY: &str = "last-seen-version";
+const CURRENT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Release {
+    pub version: String,
+    pub changes: Vec<String>,
+}
+
+fn releases() -> Vec<Release> {
+    serde_json::from_str(include_str!("changelog.json")).unwrap_or_else(|e| {
+        log::warn!("Failed to parse changelog: {:?}", e);
+        Vec::new()
+    })
+}
+
+fn parse_version(version: &str) -> (u32, u32, u32) {
+    let mut parts = version.split('.').map(|p| p.parse().unwrap_or(0));
+    (parts.next().unwrap_or(0), parts.next().unwrap_or(0), parts.next().unwrap_or(0))
+}
+
+fn last_seen(namespace: &Namespace) -> Option<String> {
+    LocalStorage::get(namespace.key(LAST_SEEN_KEY)).ok()
+}
+
+pub fn unseen(namespace: &Namespace) -> Vec<Release> {
+    let current = parse_version(CURRENT_VERSION);
+    let seen = last_seen(namespace).map(|v| parse_version(&v));
+    if seen == Some(current) {
+        return Vec::new();
+    }
+
+    releases().into_iter()
+        .filter(|r| {
+            let version = parse_version(&r.version);
+            version <= current && seen.map(|s| version > s).unwrap_or(version == current)
+        })
+        .collect()
+}
+
+pub fn mark_seen(namespace: &Namespace) {
+    if let Err(e) = LocalStorage::set(namespace.key(LAST_SEEN_KEY), CURRENT_VERSION) {
+        log::warn!("Failed to save last seen version: {:?}", e);
+    }
+}
+
+pub fn view(releases: &[Release], ondismiss: Callback<MouseEvent>) -> Html {
+    html! {
+        <div class="overlay">
+        <h3>{ "更新了啥" }</h3>
+        { releases.iter().map(|release| {
+            html! {
+                <>
+                <h4>{ &release.version }</h4>
+                <ul>
+                { release.changes.iter().map(|change| html! { <li>{ change }</li> }).collect::<Html>() }
+                </ul>
+                </>
+            }
+        }).collect::<Html>() }
+        <button onclick={ondismiss}>{ "晓得了" }</button>
+        </div>
+    }
+}
@@ -0,0 +1,71 @@
+use web_sys::HtmlElement;
+use yew::{html, Callback, Component, Context, Html, KeyboardEvent, NodeRef, Properties};
+
+#[derive(Properties, PartialEq)]
+pub struct ConfirmProps {
+    pub message: String,
+    pub yes: &'static str,
+    pub no: &'static str,
+    pub onanswer: Callback<bool>,
+}
+
+// A yes-or-no question in front of everything else. It takes focus on No, so
+// an Enter that was already on its way lands on the safe answer. Y and N
+// answer straight away, Escape is No, and Tab or the arrows switch buttons.
+pub struct Confirm {
+    yes: NodeRef,
+    no: NodeRef,
+}
+
+fn focus(node: &NodeRef) {
+    if let Some(element) = node.cast::<HtmlElement>() {
+        if let Err(e) = element.focus() {
+            log::warn!("Failed to focus the dialog: {:?}", e);
+        }
+    }
+}
+
+impl Component for Confirm {
+    type Message = ();
+    type Properties = ConfirmProps;
+
+    fn create(_ctx: &Context<Self>) -> Self {
+        Self { yes: NodeRef::default(), no: NodeRef::default() }
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        let props = ctx.props();
+        let onkeydown = {
+            let onanswer = props.onanswer.clone();
+            let (yes, no) = (self.yes.clone(), self.no.clone());
+            Callback::from(move |event: KeyboardEvent| {
+                // Whatever is underneath, the board included, listens for keys too.
+                event.stop_propagation();
+                match event.key().as_str() {
+                    "y" | "Y" => onanswer.emit(true),
+                    "n" | "N" | "Escape" => onanswer.emit(false),
+                    "Tab" | "ArrowLeft" | "ArrowRight" | "ArrowUp" | "ArrowDown" => {
+                        event.prevent_default();
+                        let active = web_sys::window().and_then(|w| w.document()).and_then(|d| d.active_element());
+                        let on_yes = active.is_some() && active.map(Into::into) == yes.get();
+                        focus(if on_yes { &no } else { &yes });
+                    }
+                    _ => {}
+                }
+            })
+        };
+        html! {
+            <div class="overlay confirm" role="alertdialog" aria-modal="true" aria-labelledby="confirm-message" {onkeydown}>
+            <p id="confirm-message">{ &props.message }</p>
+            <button ref={self.yes.clone()} onclick={props.onanswer.reform(|_| true)}>{ format!("{}（Y）", props.yes) }</button>
+            <button ref={self.no.clone()} onclick={props.onanswer.reform(|_| false)}>{ format!("{}（N）", props.no) }</button>
+            </div>
+        }
+    }
+
+    fn rendered(&mut self, _ctx: &Context<Self>, first_render: bool) {
+        if first_render {
+            focus(&self.no);
+        }
+    }
+}
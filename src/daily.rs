@@ -0,0 +1,231 @@
+use std::collections::BTreeMap;
+
+use gloo::storage::{LocalStorage, Storage};
+use serde::{Deserialize, Serialize};
+use yew::{html, Html};
+
+use crate::storage::Namespace;
+use crate::theme::DisplayMode;
+
+const DAILY_KEY: &str = "daily";
+const WEEKDAYS: [&str; 7] = ["一", "二", "三", "四", "五", "六", "日"];
+const HEAT_LEVELS: u64 = 4;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DayResult {
+    pub score: u64,
+    pub highest_tile: u64,
+    pub moves: u64,
+    pub won: bool,
+}
+
+// Kept apart from the leaderboard: one best result per UTC day.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Daily {
+    results: BTreeMap<String, DayResult>,
+    #[serde(skip)]
+    namespace: Namespace,
+}
+
+impl Daily {
+    pub fn load(namespace: &Namespace) -> Self {
+        let daily: Self = LocalStorage::get(namespace.key(DAILY_KEY)).unwrap_or_default();
+        Self { namespace: namespace.clone(), ..daily }
+    }
+
+    fn save(&self) {
+        if let Err(e) = LocalStorage::set(self.namespace.key(DAILY_KEY), self) {
+            log::warn!("Failed to save daily results: {:?}", e);
+        }
+    }
+
+    pub fn record(&mut self, date: &str, result: DayResult) {
+        if self.keep(date, result) {
+            self.save();
+        }
+    }
+
+    // Replaying a day only replaces its result with a better one.
+    fn keep(&mut self, date: &str, result: DayResult) -> bool {
+        let better = self.results.get(date).is_none_or(|best| result.score > best.score);
+        if better {
+            self.results.insert(date.to_string(), result);
+        }
+        better
+    }
+
+    pub fn played(&self, date: &str) -> bool {
+        self.results.contains_key(date)
+    }
+
+    pub fn any_played(&self) -> bool {
+        !self.results.is_empty()
+    }
+
+    // Today not being played yet doesn't break the streak until tomorrow.
+    pub fn current_streak(&self, today: &str) -> u32 {
+        let played: Vec<i64> = self.results.keys().filter_map(|date| day_number(date)).collect();
+        let today = match day_number(today) {
+            Some(today) => today,
+            None => return 0,
+        };
+        let mut day = if played.contains(&today) { today } else { today - 1 };
+        let mut streak = 0;
+        while played.contains(&day) {
+            streak += 1;
+            day -= 1;
+        }
+        streak
+    }
+
+    pub fn best_streak(&self) -> u32 {
+        let mut best = 0;
+        let mut run = 0;
+        let mut previous = None;
+        for day in self.results.keys().filter_map(|date| day_number(date)) {
+            run = if previous == Some(day - 1) { run + 1 } else { 1 };
+            best = best.max(run);
+            previous = Some(day);
+        }
+        best
+    }
+
+    // Shaded against the best daily score so far, so the hottest cell is always the record.
+    fn heat(&self, result: &DayResult) -> u64 {
+        let best = self.results.values().map(|r| r.score).max().unwrap_or(0).max(1);
+        (result.score * HEAT_LEVELS).div_ceil(best).clamp(1, HEAT_LEVELS)
+    }
+
+    pub fn view(&self, today: &str, display: DisplayMode) -> Html {
+        let (year, month) = match parse(today) {
+            Some((year, month, _)) => (year, month),
+            None => return html! {},
+        };
+        let blanks = weekday(year, month, 1);
+        let days: Vec<Option<u32>> = (0..blanks).map(|_| None)
+            .chain((1..=days_in_month(year, month)).map(Some))
+            .collect();
+        html! {
+            <div class="daily">
+            <p>{ format!("{} 的每日挑战：今天大家拿到的是同一串方块", today) }</p>
+            if let Some(best) = self.results.get(today) {
+                <p>{ format!("今天最好：{}分，最大方块{}", best.score, display.format(best.highest_tile)) }</p>
+            }
+            <p>{ format!("连续{}天，最长{}天", self.current_streak(today), self.best_streak()) }</p>
+            <table class="calendar">
+            <tr>{ WEEKDAYS.iter().map(|day| html! { <th>{ *day }</th> }).collect::<Html>() }</tr>
+            { days.chunks(7).map(|week| html! {
+                <tr>
+                { week.iter().map(|day| match day {
+                    Some(day) => {
+                        let date = format!("{:04}-{:02}-{:02}", year, month, day);
+                        match self.results.get(&date) {
+                            Some(result) => {
+                                let class = format!("heat-{}{}", self.heat(result), if result.won { " won" } else { "" });
+                                html! { <td {class} title={format!("{}分", result.score)}>{ day }</td> }
+                            }
+                            None => html! { <td class={if date == today { "today" } else { "" }}>{ day }</td> },
+                        }
+                    }
+                    None => html! { <td></td> },
+                }).collect::<Html>() }
+                </tr>
+            }).collect::<Html>() }
+            </table>
+            </div>
+        }
+    }
+}
+
+// FNV-1a over the date, so every player's RNG starts from the same state.
+pub fn seed(date: &str) -> u64 {
+    date.bytes().fold(0xcbf29ce484222325, |hash, byte| (hash ^ byte as u64).wrapping_mul(0x100000001b3))
+}
+
+fn parse(date: &str) -> Option<(i32, u32, u32)> {
+    let mut parts = date.split('-').map(str::parse::<u32>);
+    let year = parts.next()?.ok()? as i32;
+    let month = parts.next()?.ok().filter(|m| (1..=12).contains(m))?;
+    let day = parts.next()?.ok()?;
+    Some((year, month, day))
+}
+
+// Days since 0000-03-01 in the proleptic Gregorian calendar; only differences matter.
+fn day_number(date: &str) -> Option<i64> {
+    let (year, month, day) = parse(date)?;
+    let y = if month <= 2 { year - 1 } else { year } as i64;
+    let era = y.div_euclid(400);
+    let year_of_era = y - era * 400;
+    let day_of_year = (153 * ((month as i64 + 9) % 12) + 2) / 5 + day as i64 - 1;
+    Some(era * 146097 + year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year)
+}
+
+fn days_in_month(year: i32, month: u32) -> u32 {
+    match month {
+        2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+// Sakamoto's method, shifted so Monday is 0.
+fn weekday(year: i32, month: u32, day: u32) -> u32 {
+    const OFFSETS: [i32; 12] = [0, 3, 2, 5, 0, 3, 5, 1, 4, 6, 2, 4];
+    let y = if month < 3 { year - 1 } else { year };
+    let sunday_first = (y + y / 4 - y / 100 + y / 400 + OFFSETS[month as usize - 1] + day as i32).rem_euclid(7);
+    ((sunday_first + 6) % 7) as u32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result(score: u64) -> DayResult {
+        DayResult { score, highest_tile: 64, moves: 30, won: false }
+    }
+
+    fn played(dates: &[&str]) -> Daily {
+        let mut daily = Daily::default();
+        for date in dates {
+            daily.keep(date, result(100));
+        }
+        daily
+    }
+
+    #[test]
+    fn streaks_run_across_month_and_year_ends() {
+        let daily = played(&["2023-12-30", "2023-12-31", "2024-01-01", "2024-01-31", "2024-02-01", "2024-02-02"]);
+        assert_eq!(daily.current_streak("2024-02-02"), 3);
+        // Not played yet today; yesterday's streak still stands.
+        assert_eq!(daily.current_streak("2024-02-03"), 3);
+        assert_eq!(daily.current_streak("2024-02-04"), 0);
+        assert_eq!(daily.current_streak("2024-01-01"), 3);
+        assert_eq!(daily.best_streak(), 3);
+
+        let leap = played(&["2024-02-28", "2024-02-29", "2024-03-01"]);
+        assert_eq!(leap.current_streak("2024-03-01"), 3);
+        let common = played(&["2023-02-28", "2023-03-01"]);
+        assert_eq!(common.current_streak("2023-03-01"), 2);
+    }
+
+    #[test]
+    fn a_missed_day_breaks_the_streak() {
+        let daily = played(&["2024-05-01", "2024-05-02", "2024-05-04"]);
+        assert_eq!(daily.current_streak("2024-05-04"), 1);
+        assert_eq!(daily.current_streak("2024-05-03"), 2);
+        assert_eq!(daily.best_streak(), 2);
+        assert_eq!(daily.current_streak("not a date"), 0);
+    }
+
+    #[test]
+    fn replaying_a_day_keeps_its_best_result() {
+        let mut daily = Daily::default();
+        assert!(daily.keep("2024-05-01", result(300)));
+        assert!(!daily.keep("2024-05-01", result(200)));
+        assert!(daily.keep("2024-05-01", result(500)));
+        assert_eq!(daily.results.len(), 1);
+        assert_eq!(daily.results["2024-05-01"].score, 500);
+        assert_eq!(daily.current_streak("2024-05-01"), 1);
+    }
+}
@@ -0,0 +1,80 @@
+use yew::{html, Callback, Html, MouseEvent};
+
+use crate::board::{Board, Cell, Direction, Event, MoveResult, Position};
+use crate::rng::Rng;
+use crate::rules::Rules;
+use crate::settings::Action;
+
+// A move toward `direction` on a copy of the board, run one line at a time.
+// The game itself never sees it.
+pub struct Stepper {
+    pub direction: Direction,
+    board: Board,
+    heads: Vec<Position>,
+    next: usize,
+    last: Vec<Event>,
+}
+
+impl Stepper {
+    pub fn new(board: Board, direction: Direction) -> Self {
+        Self { direction, board, heads: board.line_heads(direction), next: 0, last: Vec::new() }
+    }
+
+    pub fn done(&self) -> bool {
+        self.next >= self.heads.len()
+    }
+
+    pub fn step(&mut self, rules: &dyn Rules) {
+        if let Some(&head) = self.heads.get(self.next) {
+            self.last = self.board.slide_line(rules, head, self.direction);
+            self.next += 1;
+        }
+    }
+}
+
+// Raw values, one row per line, walls as #.
+fn raw(board: &Board) -> String {
+    (0..board.rows()).map(|row| {
+        (0..board.columns()).map(|column| match board[Position{row, column}] {
+            Cell::Empty => format!("{:>6}", "."),
+            Cell::Value(v) => format!("{:>6}", v),
+            Cell::Wall => format!("{:>6}", "#"),
+        }).collect::<String>()
+    }).collect::<Vec<_>>().join("\n")
+}
+
+pub struct Panel<'a> {
+    pub board: &'a Board,
+    pub turn: &'a MoveResult,
+    pub seed: u64,
+    pub rng: Rng,
+    pub stepper: Option<&'a Stepper>,
+}
+
+pub fn view(panel: Panel, onstart: Callback<Direction>, onstep: Callback<MouseEvent>, onstop: Callback<MouseEvent>) -> Html {
+    html! {
+        <details class="debug">
+        <summary>{ "引擎调试" }</summary>
+        <p>{ format!("种子 {:#018x} · 随机数状态 {:#018x}", panel.seed, panel.rng.state()) }</p>
+        <pre>{ raw(panel.board) }</pre>
+        <p>{ "上一步的MoveResult：" }</p>
+        <pre>{ format!("{:#?}", panel.turn) }</pre>
+        <p>{ "一行一行地走：" }
+        { Direction::ALL.iter().map(|direction| {
+            let direction = *direction;
+            html! { <button onclick={onstart.reform(move |_| direction)}>{ Action::Move(direction).label() }</button> }
+        }).collect::<Html>() }
+        </p>
+        if let Some(stepper) = panel.stepper {
+            <p>{ format!("往{}：第{}/{}行", Action::Move(stepper.direction).label(), stepper.next, stepper.heads.len()) }</p>
+            <pre>{ raw(&stepper.board) }</pre>
+            if let Some(head) = stepper.next.checked_sub(1).and_then(|i| stepper.heads.get(i)) {
+                <p>{ format!("从({}, {})开始的这一行：", head.row, head.column) }</p>
+                <pre>{ format!("{:#?}", stepper.last) }</pre>
+            }
+            <button disabled={stepper.done()} onclick={onstep}>{ "下一行" }</button>
+            <button onclick={onstop}>{ "结束" }</button>
+        }
+        </details>
+    }
+}
@@ -0,0 +1,76 @@
+use crate::board::{Board, Cell, Position};
+use crate::mode::GameMode;
+use crate::theme::DisplayMode;
+
+const MAX_VALUE: u64 = 1 << 16;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Editor {
+    cursor: Option<Position>,
+    typed: String,
+}
+
+impl Editor {
+    pub fn cursor(&self) -> Option<Position> {
+        self.cursor
+    }
+
+    // Empty → the smallest tile → … → a wall → empty again.
+    pub fn cycle(&mut self, board: &mut Board, mode: GameMode, p: Position) {
+        self.cursor = Some(p);
+        self.typed.clear();
+        let next = match board[p] {
+            Cell::Empty => Cell::Value(1),
+            Cell::Value(v) => next_value(mode, v).map_or(Cell::Wall, Cell::Value),
+            Cell::Wall => Cell::Empty,
+        };
+        board.set(p.position(), next);
+    }
+
+    // Typed numbers are read the way the board shows them.
+    pub fn key(&mut self, board: &mut Board, display: DisplayMode, key: &str) -> bool {
+        let p = match self.cursor {
+            Some(p) => p,
+            None => return false,
+        };
+        let cell = match key {
+            "Backspace" | "Delete" => {
+                self.typed.clear();
+                Cell::Empty
+            }
+            "w" | "W" => {
+                self.typed.clear();
+                Cell::Wall
+            }
+            digit if digit.len() == 1 && digit.chars().all(|c| c.is_ascii_digit()) => {
+                self.typed.push_str(digit);
+                match self.typed.parse::<u64>().map(|shown| display.raw(shown)) {
+                    Ok(value) if value > 0 && value <= MAX_VALUE => Cell::Value(value),
+                    Ok(_) => Cell::Empty,
+                    Err(_) => {
+                        self.typed.clear();
+                        return false;
+                    }
+                }
+            }
+            _ => return false,
+        };
+        board.set(p.position(), cell);
+        true
+    }
+}
+
+fn next_value(mode: GameMode, value: u64) -> Option<u64> {
+    let next = match mode {
+        GameMode::Fibonacci => {
+            let (mut a, mut b) = (1u64, 2u64);
+            while a <= value {
+                (a, b) = (b, a + b);
+            }
+            a
+        }
+        GameMode::Threes if value < 3 => value + 1,
+        _ => value * 2,
+    };
+    (next <= MAX_VALUE).then_some(next)
+}
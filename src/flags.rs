@@ -0,0 +1,126 @@
+use std::collections::BTreeMap;
+
+use gloo::storage::{LocalStorage, Storage};
+use serde::{Deserialize, Serialize};
+use yew::{html, Callback, Html, MouseEvent};
+
+use crate::storage::Namespace;
+
+const FLAGS_KEY: &str = "flags";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum Flag {
+    Gamepad,
+    Animations,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Flags {
+    stored: BTreeMap<Flag, bool>,
+    query: BTreeMap<Flag, bool>,
+    namespace: Namespace,
+}
+
+impl Flag {
+    pub const ALL: [Flag; 2] = [Flag::Gamepad, Flag::Animations];
+
+    fn name(&self) -> &'static str {
+        match self {
+            Flag::Gamepad => "gamepad",
+            Flag::Animations => "animations",
+        }
+    }
+
+    fn default_enabled(&self) -> bool {
+        match self {
+            Flag::Gamepad => true,
+            Flag::Animations => true,
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Flag> {
+        Flag::ALL.iter().copied().find(|f| f.name() == name)
+    }
+}
+
+impl Flags {
+    pub fn load(namespace: &Namespace) -> Self {
+        Self {
+            stored: LocalStorage::get(namespace.key(FLAGS_KEY)).unwrap_or_default(),
+            query: query_overrides(),
+            namespace: namespace.clone(),
+        }
+    }
+
+    fn save(&self) {
+        if let Err(e) = LocalStorage::set(self.namespace.key(FLAGS_KEY), &self.stored) {
+            log::warn!("Failed to save flags: {:?}", e);
+        }
+    }
+
+    pub fn enabled(&self, flag: Flag) -> bool {
+        self.query.get(&flag)
+            .or_else(|| self.stored.get(&flag))
+            .copied()
+            .unwrap_or_else(|| flag.default_enabled())
+    }
+
+    pub fn toggle(&mut self, flag: Flag) {
+        let enabled = !self.enabled(flag);
+        self.query.remove(&flag);
+        self.stored.insert(flag, enabled);
+        self.save();
+    }
+
+    pub fn reset(&mut self) {
+        self.stored.clear();
+        self.query.clear();
+        LocalStorage::delete(self.namespace.key(FLAGS_KEY));
+    }
+
+    pub fn view(&self, ontoggle: Callback<Flag>, onreset: Callback<MouseEvent>) -> Html {
+        html! {
+            <div class="debug">
+            <h3>{ "调试菜单" }</h3>
+            <table class="stats">
+            { Flag::ALL.iter().map(|flag| {
+                let flag = *flag;
+                html! {
+                    <tr>
+                    <td>{ flag.name() }</td>
+                    <td><input type="checkbox" checked={self.enabled(flag)} onclick={ontoggle.reform(move |_| flag)} /></td>
+                    </tr>
+                }
+            }).collect::<Html>() }
+            </table>
+            <button onclick={onreset}>{ "恢复默认" }</button>
+            </div>
+        }
+    }
+}
+
+pub fn query_param(name: &str) -> Option<String> {
+    let search = web_sys::window()?.location().search().ok()?;
+    web_sys::UrlSearchParams::new_with_str(&search).ok()?.get(name)
+}
+
+// ?flags=gamepad,-animations turns gamepad on and animations off for this page load.
+fn query_overrides() -> BTreeMap<Flag, bool> {
+    let mut overrides = BTreeMap::new();
+    if let Some(list) = query_param("flags") {
+        for item in list.split(',') {
+            let (name, enabled) = match item.strip_prefix('-') {
+                Some(name) => (name, false),
+                None => (item, true),
+            };
+            if let Some(flag) = Flag::from_name(name.trim()) {
+                overrides.insert(flag, enabled);
+            }
+        }
+    }
+    overrides
+}
+
+pub fn debug_requested() -> bool {
+    query_param("debug").map(|v| v == "1").unwrap_or(false)
+}
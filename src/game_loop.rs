@@ -0,0 +1,78 @@
+use std::collections::BTreeMap;
+
+pub const STEP_MS: f64 = 1000.0 / 60.0;
+const MAX_STEPS_PER_FRAME: u32 = 30;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Timer {
+    Clock,
+    Animation,
+    Toast,
+    Turbo,
+    Demo,
+    Tournament,
+    Analysis,
+    Replay,
+    Popups,
+    Intro,
+    Autosave,
+}
+
+// Fixed-timestep clock layered over requestAnimationFrame. Timers are counted in
+// whole steps, so everything scheduled here fires in a deterministic order.
+#[derive(Debug, Default)]
+pub struct GameLoop {
+    last_frame: Option<f64>,
+    accumulator: f64,
+    tick: u64,
+    timers: BTreeMap<Timer, u64>,
+}
+
+impl GameLoop {
+    pub fn now(&self) -> f64 {
+        self.tick as f64 * STEP_MS
+    }
+
+    pub fn schedule(&mut self, timer: Timer, after_ms: u32) {
+        let steps = ((after_ms as f64 / STEP_MS).ceil() as u64).max(1);
+        self.timers.insert(timer, self.tick + steps);
+    }
+
+    pub fn cancel(&mut self, timer: Timer) {
+        self.timers.remove(&timer);
+    }
+
+    pub fn pending(&self, timer: Timer) -> bool {
+        self.timers.contains_key(&timer)
+    }
+
+    pub fn idle(&self) -> bool {
+        self.timers.is_empty()
+    }
+
+    pub fn frame(&mut self, now: f64) -> u32 {
+        let elapsed = self.last_frame.map(|last| now - last).unwrap_or(0.0);
+        self.last_frame = Some(now);
+        self.accumulator = (self.accumulator + elapsed).min(STEP_MS * MAX_STEPS_PER_FRAME as f64);
+        let steps = (self.accumulator / STEP_MS) as u32;
+        self.accumulator -= steps as f64 * STEP_MS;
+        steps
+    }
+
+    pub fn step(&mut self) -> Vec<Timer> {
+        self.tick += 1;
+        let due: Vec<Timer> = self.timers.iter()
+            .filter(|(_, at)| **at <= self.tick)
+            .map(|(timer, _)| *timer)
+            .collect();
+        for timer in &due {
+            self.timers.remove(timer);
+        }
+        due
+    }
+
+    pub fn suspend(&mut self) {
+        self.last_frame = None;
+        self.accumulator = 0.0;
+    }
+}
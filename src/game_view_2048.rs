@@ -1,332 +1,2781 @@
-use yew::{events::KeyboardEvent, html, Component, Context, Html};
-use std::ops::{Index, IndexMut};
+use yew::{events::{KeyboardEvent, TouchEvent}, html, Callback, Component, Context, Html, NodeRef, Properties, TargetCast};
+use web_sys::{Element, Event as DomEvent, HtmlCanvasElement, HtmlElement, HtmlInputElement};
+use wasm_bindgen::JsCast;
+use std::collections::VecDeque;
 
-use wasm_bindgen::prelude::*;
+use gloo::events::EventListener;
+use gloo::render::{request_animation_frame, AnimationFrame};
 
-#[wasm_bindgen(inline_js="export function set_focus() {document.getElementById(\"gameplay\").focus();}")]
-extern "C" {
-    fn set_focus();
+use crate::achievements::{self, Achievements, Progress, Toast};
+use crate::ai::{self, MoveEvaluation};
+use crate::analysis::Analysis;
+use crate::audio::{self, Audio, Sound};
+use crate::autosave::{self, Autosave};
+use crate::backup::{self, ImportError};
+use crate::bench::{self, BenchResult};
+use crate::board::{Board, Cell, Direction, Event, Merge, MoveResult, Position};
+use crate::board_view::BoardView;
+use crate::changelog::{self, Release};
+use crate::confirm::{Confirm, ConfirmProps};
+use crate::daily::{self, Daily, DayResult};
+use crate::debugger::{self, Panel, Stepper};
+use crate::editor::Editor;
+use crate::flags::{self, Flag, Flags};
+use crate::game_loop::{GameLoop, Timer};
+use crate::gamepad;
+use crate::ghost::{Ghost, Run, Runs};
+use crate::global::{self, GlobalEntry, Submission};
+use crate::handoff::Handoff;
+use crate::haptics;
+use crate::hint;
+use crate::input::{self, Focus, InputPipeline, KeyPress, RawInput, Source};
+use crate::inspector;
+use crate::layout::Viewport;
+use crate::leaderboard::{self, Entry, Leaderboard};
+use crate::mascot::{self, Mood, Reaction};
+use crate::mode::{self, GameMode};
+use crate::narration::{self, MoveSummary};
+use crate::powerups::{PowerUp, PowerUps, Selection};
+use crate::preview;
+use crate::perf::{self, Perf};
+use crate::puzzles::{self, Authoring, Draft, Puzzle, Solved, Status};
+use crate::pwa;
+use crate::race::{self, Report, Seat};
+use crate::records::Records;
+use crate::replay::{self, Loader, Replay, Step};
+use crate::rng::Rng;
+use crate::seasons::{self, Season};
+use crate::settings::{Action, KeyBindings, Settings};
+use crate::sparkline;
+use crate::spawn::SpawnPolicy;
+use crate::stats::Stats;
+use crate::storage::{self, Estimate, Namespace};
+use crate::summary::{self, Summary};
+use crate::theme::{self, DisplayMode};
+use crate::tile_view::TileView;
+use crate::totals::{Outcome, Totals};
+use crate::tournament::{Policy, Tournament};
+use crate::tutorial::{self, Tutorial};
+
+pub enum Msg {
+    Input(RawInput),
+    KeyReleased(String),
+    Preview(Option<Direction>),
+    PadDown(Direction),
+    PadUp(Direction, bool),
+    Blurred,
+    Refocus,
+    ToggleEditor,
+    ToggleHint,
+    ToggleTournament,
+    RunBench,
+    TogglePerf,
+    StartStepping(Direction),
+    StepLine,
+    StopStepping,
+    SetInspected(Cell),
+    CloseInspector,
+    SetPolicy((usize, Policy)),
+    SetTournamentGames(u32),
+    StartTournament,
+    StopTournament,
+    Pause,
+    ReadBoard,
+    Resume,
+    VisibilityChanged,
+    Resized,
+    Undo,
+    Redo,
+    Restart,
+    ToggleSettings,
+    Rebind(Action),
+    ResetKeyBindings,
+    ChangeSettings(Settings),
+    ToggleLeaderboard,
+    ShowGlobal(bool),
+    GlobalLoaded(Result<Vec<GlobalEntry>, String>),
+    GlobalSubmitted(Result<(), (Box<Submission>, String)>),
+    Online,
+    Autosave,
+    InstallAvailable(DomEvent),
+    Install,
+    Installed,
+    SetPlayerName(String),
+    ClearLeaderboard,
+    DismissChangelog,
+    EndTutorial,
+    ToggleFlag(Flag),
+    ResetFlags,
+    ResetTotals,
+    SetMode(GameMode),
+    Answer(bool),
+    PlayAgain,
+    KeepPlaying,
+    ShowContinuation,
+    DownloadSummary,
+    CloseContinuation,
+    SelectPowerUp(PowerUp),
+    ToggleStorage,
+    ToggleHandoff,
+    ApplyHandoff(String),
+    ToggleReplay,
+    TogglePuzzles,
+    StartPuzzle(usize),
+    EditDraft(Draft),
+    SharePuzzle,
+    ToggleHeatmap,
+    DownloadReplay,
+    ChooseReplay(HtmlInputElement),
+    LoadReplay(String),
+    StorageEstimated(Option<Estimate>),
+    DeleteStored(String),
+    ExportData,
+    ChooseBackup(HtmlInputElement),
+    LoadBackup(String),
+    CellClicked(Position),
+    GamepadConnected,
+    Frame(f64),
+}
+
+#[derive(Properties, Default, PartialEq)]
+pub struct GameProps {
+    #[prop_or_default]
+    pub instance: String,
+    // Set when the board is one side of a race or an online game: its keys,
+    // the shared seed and where to send its progress.
+    #[prop_or_default]
+    pub seat: Option<Seat>,
+    #[prop_or_default]
+    pub seed: Option<u64>,
+    // Junk tiles the other side of a battle has sent so far.
+    #[prop_or_default]
+    pub garbage: u32,
+    #[prop_or_default]
+    pub onreport: Callback<Report>,
+    // Every move's result, in order, for broadcasting.
+    #[prop_or_default]
+    pub onturn: Callback<MoveResult>,
 }
 
-#[derive(Debug, Clone, Copy)]
-pub enum Direction {
-    Up, Down, Left, Right
+// Won stops play until the player picks 继续玩; WonContinuing then plays on
+// until the board fills up.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum GamePhase {
+    Playing,
+    Won,
+    Lost,
+    WonContinuing,
 }
 
-#[derive(Debug, Clone, Copy)]
-struct Position {
-    row: u8,
-    column: u8,
+impl GamePhase {
+    fn from_flags(won: bool, is_dead: bool) -> Self {
+        match (won, is_dead) {
+            (_, true) => GamePhase::Lost,
+            (true, false) => GamePhase::Won,
+            (false, false) => GamePhase::Playing,
+        }
+    }
+
+    fn over(self) -> bool {
+        matches!(self, GamePhase::Won | GamePhase::Lost)
+    }
+
+    fn reached_goal(self) -> bool {
+        matches!(self, GamePhase::Won | GamePhase::WonContinuing)
+    }
+
+    // After the board was edited by hand: only whether it is stuck can change.
+    fn judged(self, dead: bool) -> Self {
+        match self {
+            _ if dead => GamePhase::Lost,
+            GamePhase::Lost => GamePhase::Playing,
+            phase => phase,
+        }
+    }
 }
 
 pub struct GameState {
-    state: [u64; 36],
-    is_dead: bool,
-    won: bool,
+    namespace: Namespace,
+    board: Board,
+    phase: GamePhase,
+    score: u64,
+    moves: u64,
+    // FNV-1a of every direction played, so the server can check a replay.
+    replay_hash: u32,
+    stats: Stats,
+    leaderboard: Leaderboard,
+    player_name: String,
+    show_leaderboard: bool,
+    show_heatmap: bool,
+    preview: Option<Direction>,
+    pad_pressed_at: f64,
+    show_global: bool,
+    // None while the top list is on its way.
+    global: Option<Result<Vec<GlobalEntry>, String>>,
+    submissions: Vec<Submission>,
+    achievements: Achievements,
+    records: Records,
+    totals: Totals,
+    // What this game already added to the totals, if it has finished before.
+    counted: Option<Outcome>,
+    toasts: VecDeque<String>,
+    input: InputPipeline,
+    frame: Option<AnimationFrame>,
+    game_loop: GameLoop,
+    gamepad_listener: Option<EventListener>,
+    _visibility_listener: Option<EventListener>,
+    viewport: Viewport,
+    _resize_listener: Option<EventListener>,
+    _online_listener: Option<EventListener>,
+    _unload_listener: Option<EventListener>,
+    autosave: Autosave,
+    _install_listeners: Vec<EventListener>,
+    install_prompt: Option<DomEvent>,
+    history: Vec<Snapshot>,
+    redo: Vec<Snapshot>,
+    score_history: Vec<u64>,
+    undos: u64,
+    settings: Settings,
+    show_settings: bool,
+    rebinding: Option<Action>,
+    pending_moves: VecDeque<Direction>,
+    turn: MoveResult,
+    // The last move's merges, floating up as +N until Timer::Popups clears them.
+    popups: Vec<Merge>,
+    popup_round: u64,
+    // How far a new game's intro has got: squares come in a diagonal per
+    // step, then the tiles pop in together.
+    intro: Option<u8>,
+    audio: Audio,
+    mood: Mood,
+    daily: Daily,
+    daily_date: Option<String>,
+    seeded: bool,
+    ghosts: Runs,
+    ghost: Option<Ghost>,
+    editor: Option<Editor>,
+    practice: bool,
+    season: Option<Season>,
+    hint: Option<(Board, Vec<MoveEvaluation>)>,
+    tournament: Option<Tournament>,
+    bench: Vec<BenchResult>,
+    inspecting: Option<Position>,
+    analysis: Analysis,
+    last_move_at: f64,
+    armed: Option<Direction>,
+    announcement: String,
+    last_move: MoveSummary,
+    turbo: Option<Direction>,
+    whats_new: Vec<Release>,
+    tutorial: Option<Tutorial>,
+    confirming: Option<Confirmation>,
+    flags: Flags,
+    show_debug: bool,
+    perf: Perf,
+    stepper: Option<Stepper>,
+    seed: u64,
+    shared_seed: Option<u64>,
+    seat: Option<Seat>,
+    report: Option<Report>,
+    outbox: Vec<MoveResult>,
+    attacks: u32,
+    garbage_taken: u32,
+    garbage_rng: Rng,
+    _key_listener: Option<EventListener>,
+    rng: Rng,
+    remaining: Option<u32>,
+    powerups: PowerUps,
+    selecting: Option<Selection>,
+    show_storage: bool,
+    storage_usage: Vec<(String, usize)>,
+    storage_estimate: Option<Estimate>,
+    demo: Option<Demo>,
+    started_at: f64,
+    finished_at: Option<f64>,
+    paused_at: Option<f64>,
+    paused_total: f64,
+    summary_canvas: NodeRef,
+    gameplay: NodeRef,
+    root: NodeRef,
+    show_handoff: bool,
+    handoff_code: Option<String>,
+    handoff_error: Option<&'static str>,
+    // Moves played so far with their spawns. Undo only moves `played` back,
+    // so redo still finds its steps; a new move cuts the rest off.
+    steps: Vec<Step>,
+    played: usize,
+    replay_start: Option<(Position, u64)>,
+    // Cleared by anything a seed can't reproduce: power-ups, junk, handoffs.
+    replayable: bool,
+    show_replay: bool,
+    replay_error: Option<String>,
+    playback: VecDeque<Direction>,
+    replay_loader: Option<Loader>,
+    puzzles: Vec<Puzzle>,
+    // Index into `puzzles` of the one on the board.
+    puzzle: Option<usize>,
+    // The player's mode and spawn policy while a puzzle plays with its own.
+    // They're what gets saved meanwhile, and they come back with the next game.
+    set_aside: Option<(GameMode, SpawnPolicy)>,
+    solved_puzzles: Solved,
+    show_puzzles: bool,
+    draft: Draft,
+    puzzle_link: Option<String>,
+    puzzle_error: Option<String>,
+    backup_loader: Option<Loader>,
+    imported: Option<Result<usize, ImportError>>,
+}
+
+struct Demo {
+    board: Board,
+    rng: Rng,
+    score: u64,
+    moves: u64,
+    rewound: usize,
+    finished: bool,
+}
+
+// Things that throw away a game in progress, held until the player says yes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Confirmation {
+    Restart,
+    Mode(GameMode),
 }
 
-struct LineIteration {
-    head: Position,
-    direction: Direction,
-    ended: bool,
-}
+#[derive(Clone)]
+struct Snapshot {
+    board: Board,
+    phase: GamePhase,
+    score: u64,
+    moves: u64,
+    replay_hash: u32,
+    stats: Stats,
+    rng: Rng,
+    powerups: PowerUps,
+    played: usize,
+}
+
+const HISTORY_LIMIT: usize = 64;
+const LOW_MEMORY_HISTORY_LIMIT: usize = 1;
+const PENDING_MOVES_LIMIT: usize = 2;
+const ANIMATION_MS: u32 = 120;
+const POPUP_MS: u32 = 800;
+const INTRO_STEP_MS: u32 = 40;
+const TOAST_MS: u32 = 3000;
+const CLOCK_MS: u32 = 1000;
+const DEMO_STEP_MS: u32 = 250;
+const TOURNAMENT_STEP_MS: u32 = 33;
+const TOURNAMENT_SLICE_MS: f64 = 8.0;
+const ANALYSIS_STEP_MS: u32 = 250;
+const DEMO_MOVE_LIMIT: u64 = 500;
+const AI_DEPTH: u32 = 2;
+const BLUNDER_MARGIN: f64 = 4.0;
+const MODIFIER_KEYS: [&str; 4] = ["Shift", "Control", "Alt", "Meta"];
+
+impl GameState {
+    fn cell(&self, x: Position) -> String {
+        match self.board[x] {
+            Cell::Value(order) => self.display().format(order),
+            _ => "".to_string(),
+        }
+    }
+
+    fn selected(&self, x: Position) -> bool {
+        let cursor = self.editor.as_ref().and_then(Editor::cursor);
+        self.selecting == Some(Selection::SwapSecond(x)) || cursor == Some(x)
+    }
+
+    // The squares are the grid and take the clicks; tiles float over them.
+    fn square_class(&self, x: Position) -> String {
+        let class = format!("square {}", theme::tile_class(self.settings.palette, Cell::Empty));
+        let class = if self.selected(x) { format!("{} selected", class) } else { class };
+        if self.intro.is_some_and(|step| x.row + x.column >= step) { format!("{} assembling", class) } else { class }
+    }
+
+    // Each tile is placed by its own transform rather than by the grid, so it
+    // can travel between squares instead of jumping. Pop and tile-age styles go
+    // on the inside, where they don't fight over `transform`.
+    fn tile(&self, x: Position) -> Html {
+        let merges = self.board.meta(x).merges;
+        html! {
+            <TileView key={self.board.meta(x).id} position={x} class={self.cell_class(x)} style={self.cell_style(x)}
+                text={self.cell(x)}
+                badge={(self.settings.show_merge_badges && merges > 0).then_some(merges)}
+                popup={self.popups.iter().find(|merge| merge.into == x).map(|merge| (self.popup_round, merge.value))} />
+        }
+    }
+
+    // In id order rather than board order, so a slide changes a tile's
+    // transform without moving its node.
+    fn tiles(&self) -> Html {
+        if self.intro.is_some_and(|step| step < self.intro_diagonals()) {
+            return html! {};
+        }
+        let mut tiles: Vec<Position> = self.board.positions().filter(|p| self.board[*p] != Cell::Empty).collect();
+        tiles.sort_by_key(|p| self.board.meta(*p).id);
+        tiles.into_iter().map(|p| self.tile(p)).collect()
+    }
+
+    fn cell_class(&self, x: Position) -> String {
+        let class = theme::tile_class(self.settings.palette, self.board[x]);
+        let class = if self.selected(x) { format!("{} selected", class) } else { class };
+        if self.animates() && (self.lands_at(x) || self.intro.is_some()) {
+            format!("{} pop", class)
+        } else {
+            class
+        }
+    }
+
+    fn cell_style(&self, x: Position) -> String {
+        if !self.settings.show_tile_age || self.board[x] == Cell::Empty {
+            return "".to_string();
+        }
+        format!("filter: hue-rotate({}deg);", (self.board.meta(x).age * 15).min(180))
+    }
+
+    fn cell_label(&self, x: Position) -> String {
+        let content = match self.board[x] {
+            Cell::Empty => "空".to_string(),
+            Cell::Value(order) => format!("方块{}", self.display().format(order)),
+            Cell::Wall => "墙".to_string(),
+        };
+        format!("第{}行第{}列，{}", x.row + 1, x.column + 1, content)
+    }
+
+    // Shaded by how many of this game's merges landed here, against the busiest square.
+    fn cell_heat(&self, x: Position) -> Html {
+        let busiest = self.stats.busiest_cell();
+        if !self.show_heatmap || busiest == 0 {
+            return html! {};
+        }
+        let heat = self.stats.merges_at(x) as f64 / busiest as f64;
+        html! { <span class="heat" style={format!("opacity: {:.2};", heat * 0.6)} title={format!("合并{}次", self.stats.merges_at(x))} /> }
+    }
+
+    fn display(&self) -> DisplayMode {
+        if self.settings.mode.doubles() { self.settings.display } else { DisplayMode::Raw }
+    }
+
+    fn animates(&self) -> bool {
+        self.flags.enabled(Flag::Animations) && !self.settings.reduces_motion()
+    }
+
+    fn wins(&self) -> bool {
+        self.settings.mode.rules().wins(self.board.highest())
+    }
+
+    fn over(&self) -> bool {
+        self.phase.over() || self.remaining == Some(0)
+    }
+
+    fn shitword(&self) -> String {
+        let keys = &self.settings.key_bindings;
+        if self.remaining == Some(0) {
+            return format!("时间到！{}分。", self.score);
+        }
+        if let Some(i) = self.puzzle {
+            match self.puzzles[i].status(&self.board, self.moves) {
+                Status::Solved => return format!("解出来了！用了{}步。", self.moves),
+                Status::Failed => return format!("步数用完了。按{}悔棋再想想。", keys.key(Action::Undo)),
+                Status::Solving { .. } => {}
+            }
+        }
+        if self.phase == GamePhase::Won {
+            return format!("你nb。想重新开始？按{}。", keys.key(Action::Restart));
+        }
+        if self.phase == GamePhase::Lost {
+            return format!("你寄了。按{}悔棋，按{}重新开始。", keys.key(Action::Undo), keys.key(Action::Restart));
+        }
+
+        format!("按{}/{}/{}/{}操作晓得的不咯？",
+            keys.key(Action::Move(Direction::Up)),
+            keys.key(Action::Move(Direction::Left)),
+            keys.key(Action::Move(Direction::Down)),
+            keys.key(Action::Move(Direction::Right)))
+    }
+
+    // Held back while the last move is still animating, so the tile that
+    // ended the game is on screen before the report covers the page.
+    fn overlay(&self, ctx: &Context<Self>) -> Html {
+        if !self.over() || self.game_loop.pending(Timer::Animation) {
+            return html! {};
+        }
+
+        let link = ctx.link();
+        html! {
+            <div class="overlay">
+            <p>{ self.shitword() }</p>
+            { self.stats.view() }
+            { sparkline::graph(&self.scores()) }
+            { self.analysis.view() }
+            <canvas class="summary" ref={self.summary_canvas.clone()} />
+            <button onclick={link.callback(|_| Msg::DownloadSummary)}>{ "下载PNG" }</button>
+            if let Some(i) = self.puzzle {
+                <button onclick={link.callback(move |_| Msg::StartPuzzle(i))}>{ "再试一次" }</button>
+                <button onclick={link.callback(|_| Msg::TogglePuzzles)}>{ "换一个残局" }</button>
+            } else if self.phase == GamePhase::Won && self.remaining != Some(0) {
+                <button onclick={link.callback(|_| Msg::KeepPlaying)}>{ "继续玩" }</button>
+            }
+            if self.remaining == Some(0) {
+                <button onclick={link.callback(|_| Msg::PlayAgain)}>{ "同一种子再来一局" }</button>
+            }
+            if let Some(demo) = &self.demo {
+                <p>{ format!("AI从{}步前接手：{}分，走了{}步{}", demo.rewound, demo.score, demo.moves, if demo.finished { "，结束" } else { "…" }) }</p>
+                <BoardView board={demo.board} palette={self.settings.palette} display={self.display()} />
+                <button onclick={link.callback(|_| Msg::CloseContinuation)}>{ "关闭演示" }</button>
+            } else if !self.history.is_empty() {
+                <button onclick={link.callback(|_| Msg::ShowContinuation)}>{ "看看AI会怎么走" }</button>
+            }
+            </div>
+        }
+    }
+
+    // Score after every move so far, ending with the current one.
+    fn scores(&self) -> Vec<u64> {
+        [self.score_history.as_slice(), &[self.score]].concat()
+    }
+
+    fn summary(&self) -> Summary {
+        let end = self.finished_at.unwrap_or_else(js_sys::Date::now);
+        Summary {
+            mode: self.settings.mode.label(),
+            score: self.score,
+            highest_tile: self.display().format(self.board.highest()),
+            moves: self.moves,
+            seconds: ((end - self.started_at - self.paused_total) / 1000.0).max(0.0) as u64,
+            best_move_score: self.stats.best_move_score,
+            most_merges_in_move: self.stats.most_merges_in_move,
+            longest_combo: self.stats.longest_combo,
+        }
+    }
+
+    // The one place game state changes during play: the board takes the event
+    // and score and stats are folded from it.
+    fn apply_event(&mut self, event: Event) {
+        self.board.apply_event(event);
+        match event {
+            Event::Merge { into, value, .. } => {
+                self.score += value;
+                self.stats.record_merge(into, value);
+            }
+            Event::Spawn { value, .. } => self.stats.record_tile(value),
+            _ => {}
+        }
+    }
+
+    fn apply_events(&mut self, events: &[Event]) {
+        for &event in events {
+            self.apply_event(event);
+        }
+    }
+
+    fn evaluate_hint(&mut self) -> Option<(Board, Vec<MoveEvaluation>)> {
+        let (evaluations, ms) = perf::timed(|| self.settings.solver.solver().evaluate_moves(&self.board, self.settings.mode.rules()));
+        self.perf.solved("提示", ms);
+        Some((self.board, evaluations))
+    }
+
+    // An open hint panel follows the board, so it is recomputed whenever the board changes.
+    fn refresh_hint(&mut self) {
+        if let Some((board, _)) = &self.hint {
+            if *board != self.board {
+                self.hint = self.evaluate_hint();
+            }
+        }
+    }
+
+    // Always judged by expectimax: BLUNDER_MARGIN is on its scale. Returns
+    // how much the move gave up.
+    fn blunder(&mut self, direction: Direction) -> Option<f64> {
+        let (evaluations, ms) = perf::timed(|| ai::evaluate_moves(&self.board, self.settings.mode.rules(), AI_DEPTH));
+        self.perf.solved("失误检查", ms);
+        let best = ai::best(&evaluations)?.expected;
+        evaluations.iter()
+            .find(|e| e.direction == direction)
+            .map(|e| best - e.expected)
+            .filter(|loss| *loss > BLUNDER_MARGIN)
+    }
+
+    fn input_focus(&self) -> Focus {
+        let overlay = self.show_settings || self.show_leaderboard || self.show_handoff || self.inspecting.is_some() || self.confirming.is_some();
+        if overlay { Focus::Overlay } else { Focus::Board }
+    }
+
+    // Menus, the pause screen and the post-game report all leave the board alone.
+    fn idle(&self) -> bool {
+        self.paused_at.is_some() || self.show_settings || self.show_leaderboard || self.over()
+    }
+
+    fn schedule_analysis(&mut self) {
+        if self.settings.idle_analysis && self.idle() && self.analysis.pending() && !self.game_loop.pending(Timer::Analysis) {
+            self.game_loop.schedule(Timer::Analysis, ANALYSIS_STEP_MS);
+        }
+    }
+
+    fn play_sounds(&mut self) {
+        if self.settings.muted {
+            return;
+        }
+        let mut sounds = audio::for_move(&self.turn);
+        if self.phase == GamePhase::Won {
+            sounds = vec![Sound::Win];
+        } else if self.phase == GamePhase::Lost {
+            sounds.push(Sound::GameOver);
+        }
+        for sound in sounds {
+            self.audio.play(sound, self.settings.volume);
+        }
+    }
+
+    fn lands_at(&self, p: Position) -> bool {
+        // Seasonal mutators can spawn more than one tile, so spawns come from the events.
+        self.turn.merges.iter().any(|merge| merge.into == p)
+            || self.turn.events.iter().any(|event| matches!(event, Event::Spawn { position, .. } if *position == p))
+    }
+
+    fn add_at_random_position(&mut self) -> Vec<Event> {
+        let rules = self.settings.mode.rules();
+        let events = match (&self.season, self.settings.mode) {
+            (Some(season), GameMode::Event) => season.mutator.spawns(&self.board, rules, &mut self.rng),
+            _ => self.settings.spawn_policy.spawn_event(&self.board, rules, &mut self.rng).into_iter().collect(),
+        };
+        self.apply_events(&events);
+        events
+    }
+
+    fn load_leaderboard(&self) -> Leaderboard {
+        let bucket = match (&self.season, self.settings.mode) {
+            (Some(season), GameMode::Event) => Some(season.bucket(&leaderboard::today())),
+            _ => None,
+        };
+        Leaderboard::load_bucket(&self.namespace, bucket.as_deref())
+    }
+
+    fn move_summary(&self) -> MoveSummary {
+        let display = self.display();
+        MoveSummary {
+            moved: self.turn.moved,
+            merged: self.turn.merges.iter().map(|merge| display.value(merge.value)).collect(),
+            spawned: self.turn.spawned.map(|(_, value)| display.value(value)),
+            score: self.score,
+            over: self.over(),
+        }
+    }
+
+    // Everything the move changed, spawn and endless-mode clearing included.
+    fn update_state(&mut self, direction: Direction) -> MoveResult {
+        let mode = self.settings.mode;
+        let highest = self.board.highest();
+        let start = self.board;
+        let mut events = self.board.slide_events(mode.rules(), direction);
+        let moved = events.iter().any(Event::moves_tile);
+        self.apply_events(&events);
+        if let Some(milestone) = mode.milestone(highest, self.board.highest()) {
+            self.toasts.push_back(format!("里程碑：{}！", self.display().format(milestone)));
+            if self.phase == GamePhase::Playing {
+                self.phase = GamePhase::WonContinuing;
+            }
+        }
+
+        // Only a slide can win: a board that already holds the goal, after an
+        // undo or in a puzzle, doesn't end the game on a move that does nothing.
+        if moved && mode.stops_on_win() && self.phase == GamePhase::Playing && self.wins() {
+            self.phase = GamePhase::Won;
+        } else if moved {
+            events.extend(self.add_at_random_position());
+            if self.board.dead(mode.rules()) {
+                if mode.endless() {
+                    events.extend(self.clear_weakest_row());
+                } else {
+                    self.phase = GamePhase::Lost;
+                }
+            }
+        }
+
+        let result = MoveResult::on_board(&start, events);
+        if result.moved {
+            self.moves += 1;
+            self.replay_hash = global::replay_hash(self.replay_hash, direction);
+        }
+        self.stats.record_move(&result);
+        result
+    }
+
+    fn clear_weakest_row(&mut self) -> Vec<Event> {
+        let events = mode::endless_clears(&self.board);
+        self.apply_events(&events);
+        events
+    }
+
+    fn record_score(&mut self) {
+        if self.practice {
+            return;
+        }
+        let summary = self.summary();
+        let outcome = Outcome { won: self.phase.reached_goal(), score: summary.score, moves: summary.moves, seconds: summary.seconds };
+        let previous = self.counted.replace(outcome);
+        self.totals.record(self.settings.mode, outcome, previous);
+        // A game can finish more than once: winning, then losing after 继续玩,
+        // or losing again after an undo. The totals swap in the latest
+        // outcome, but entries and submissions can't be taken back, so only
+        // the first finish makes them.
+        if previous.is_some() {
+            return;
+        }
+        self.leaderboard.record(Entry {
+            name: self.player_name.clone(),
+            score: self.score,
+            highest_tile: self.stats.highest_tile,
+            moves: self.moves,
+            date: leaderboard::today(),
+        });
+        if let (true, Some(replay)) = (self.seeded, self.replay()) {
+            self.ghosts.record(Run { score: self.score, replay });
+        }
+        if self.settings.global_endpoint().is_some() {
+            self.submissions.push(Submission {
+                name: self.player_name.clone(),
+                score: self.score,
+                mode: self.settings.mode,
+                seed: self.seed,
+                moves: self.moves,
+                replay_hash: global::format_hash(self.replay_hash),
+                date: leaderboard::today(),
+            });
+        }
+    }
+
+    // Only nags people who have played a daily before.
+    fn remind_daily(&mut self) {
+        let today = leaderboard::today();
+        if !self.daily.any_played() || self.daily.played(&today) {
+            return;
+        }
+        let streak = self.daily.current_streak(&today);
+        self.toasts.push_back(if streak > 0 {
+            format!("今天的每日挑战还没玩，连续{}天要断了！", streak)
+        } else {
+            "今天的每日挑战还没玩".to_string()
+        });
+        self.schedule_toast();
+    }
+
+    // However the game just ended, by a move or the clock. A move composes
+    // its own announcement afterwards.
+    fn finish(&mut self) {
+        self.finished_at = Some(js_sys::Date::now());
+        self.game_loop.cancel(Timer::Clock);
+        self.record_score();
+        self.record_daily();
+        self.announcement = self.shitword();
+        self.autosave();
+    }
+
+    fn record_daily(&mut self) {
+        if self.practice {
+            return;
+        }
+        if let Some(date) = &self.daily_date {
+            self.daily.record(date, DayResult {
+                score: self.score,
+                highest_tile: self.stats.highest_tile,
+                moves: self.moves,
+                won: self.phase.reached_goal(),
+            });
+        }
+    }
+
+    fn check_achievements(&mut self, merges_before: u64) {
+        if self.practice {
+            return;
+        }
+        self.records.record(&self.stats);
+        let progress = Progress {
+            highest_tile: self.stats.highest_tile,
+            won: self.phase.reached_goal(),
+            undo_used: self.undos > 0,
+            merges: self.stats.total_merges - merges_before,
+            moves: self.moves,
+        };
+        self.toasts.extend(self.achievements.evaluate(&progress).into_iter().map(achievements::unlocked));
+    }
+
+    fn schedule_toast(&mut self) {
+        if !self.game_loop.pending(Timer::Toast) && !self.toasts.is_empty() {
+            self.game_loop.schedule(Timer::Toast, TOAST_MS);
+        }
+    }
+
+    fn handle_move(&mut self, direction: Direction) -> bool {
+        if self.settings.confirm_moves && self.armed != Some(direction) {
+            self.armed = Some(direction);
+            return true;
+        }
+        self.armed = None;
+
+        let now = js_sys::Date::now();
+        if now - self.last_move_at < self.settings.min_move_interval_ms as f64 {
+            return false;
+        }
+        self.last_move_at = now;
+
+        if !self.playback.is_empty() {
+            return false;
+        }
+
+        if self.game_loop.pending(Timer::Animation) {
+            if self.pending_moves.len() < PENDING_MOVES_LIMIT {
+                self.pending_moves.push_back(direction);
+            }
+            return false;
+        }
+
+        self.apply_move(direction)
+    }
+
+    fn apply_move(&mut self, direction: Direction) -> bool {
+        log::info!("Event: {:?}", direction);
+        if self.over() {
+            self.pending_moves.clear();
+            return false;
+        }
+        // A direction that slides nothing isn't a move: no tile, no undo step,
+        // no replay step and none of the reactions, so it can't buy a puzzle
+        // free spawns or hold up the next key behind an animation.
+        if !self.board.slide_events(self.settings.mode.rules(), direction).iter().any(Event::moves_tile) {
+            return false;
+        }
+
+        // Playing straight away skips the rest of the intro.
+        if self.intro.take().is_some() {
+            self.game_loop.cancel(Timer::Intro);
+        }
+        let merges_before = self.stats.total_merges;
+        let blunder = match self.settings.mascot || self.settings.idle_analysis {
+            true => self.blunder(direction),
+            false => None,
+        };
+        let before = self.board;
+        self.push_history();
+        self.turn = self.update_state(direction);
+        if let Some(i) = self.puzzle {
+            match self.puzzles[i].status(&self.board, self.moves) {
+                Status::Solved => {
+                    self.phase = GamePhase::Won;
+                    self.solved_puzzles.record(&self.puzzles[i]);
+                }
+                Status::Failed => self.phase = GamePhase::Lost,
+                Status::Solving { .. } => {}
+            }
+        }
+        self.steps.truncate(self.played);
+        self.steps.push(Step { direction, spawned: self.turn.spawned });
+        self.played += 1;
+        self.attacks += race::attacks(&self.turn);
+        self.outbox.push(self.turn.clone());
+        if let (Some(loss), true) = (blunder, self.settings.idle_analysis) {
+            self.analysis.record(self.moves, before, direction, loss);
+        }
+        if self.settings.mascot {
+            self.mood = mascot::react(Reaction {
+                turn: &self.turn,
+                blunder: blunder.is_some(),
+                empties: self.board.empties().len(),
+                won: self.phase == GamePhase::Won,
+                dead: self.phase == GamePhase::Lost,
+            });
+        }
+        self.powerups.record_merges(self.stats.total_merges - merges_before);
+        if let Some(tutorial) = &mut self.tutorial {
+            tutorial.advance(&self.board, self.settings.mode.rules());
+        }
+        self.check_achievements(merges_before);
+        self.schedule_toast();
+        if self.over() {
+            self.finish();
+        }
+        self.last_move = self.move_summary();
+        self.announcement = self.last_move.written();
+        if self.over() {
+            self.announcement = format!("{}。{}", self.announcement, self.shitword());
+        }
+        self.play_sounds();
+        if self.settings.haptics {
+            if self.phase == GamePhase::Lost {
+                haptics::game_over();
+            } else {
+                haptics::merges(&self.turn);
+            }
+        }
+        if self.settings.narration {
+            let locale = self.settings.narration_locale;
+            narration::speak(&self.last_move.spoken(locale), locale, self.settings.narration_voice.as_deref());
+        }
+        self.autosave();
+
+        if self.animates() {
+            self.game_loop.schedule(Timer::Animation, ANIMATION_MS);
+            if !self.turn.merges.is_empty() {
+                self.popups = self.turn.merges.clone();
+                self.popup_round += 1;
+                self.game_loop.schedule(Timer::Popups, POPUP_MS);
+            }
+        }
+        true
+    }
+
+    fn apply_power_up(&mut self, event: Event) {
+        self.turn = MoveResult::on_board(&self.board, vec![event]);
+        self.apply_event(event);
+        self.replayable = false;
+    }
+
+    fn select_power_up(&mut self, power_up: PowerUp) {
+        if self.selecting.map(|s| s.power_up()) == Some(power_up) {
+            self.selecting = None;
+        } else if self.powerups.available(power_up) > 0 && !self.over() {
+            self.selecting = Some(Selection::start(power_up));
+        }
+    }
+
+    fn cell_clicked(&mut self, p: Position) -> bool {
+        if let Some(editor) = &mut self.editor {
+            editor.cycle(&mut self.board, self.settings.mode, p);
+            return true;
+        }
+        if self.show_debug && self.selecting.is_none() {
+            self.inspecting = (self.inspecting != Some(p)).then_some(p);
+            return true;
+        }
+        let is_tile = matches!(self.board[p], Cell::Value(_));
+        match self.selecting {
+            Some(Selection::Bomb) if is_tile => {
+                self.push_history();
+                self.apply_power_up(Event::Clear { position: p });
+                self.powerups.consume(PowerUp::Bomb);
+                self.selecting = None;
+                true
+            }
+            Some(Selection::SwapFirst) if is_tile => {
+                self.selecting = Some(Selection::SwapSecond(p));
+                true
+            }
+            Some(Selection::SwapSecond(first)) if first == p => {
+                self.selecting = Some(Selection::SwapFirst);
+                true
+            }
+            Some(Selection::SwapSecond(first)) if is_tile => {
+                self.push_history();
+                self.apply_power_up(Event::Swap { a: first, b: p });
+                self.powerups.consume(PowerUp::Swap);
+                self.selecting = None;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn show_continuation(&mut self) {
+        let rewound = (self.settings.demo_rewind as usize).min(self.history.len());
+        let start = match self.history.get(self.history.len() - rewound) {
+            Some(snapshot) => snapshot.clone(),
+            None => self.snapshot(),
+        };
+        self.demo = Some(Demo {
+            board: start.board,
+            rng: start.rng,
+            score: start.score,
+            moves: 0,
+            rewound,
+            finished: false,
+        });
+        self.game_loop.schedule(Timer::Demo, DEMO_STEP_MS);
+    }
+
+    fn close_continuation(&mut self) {
+        self.demo = None;
+        self.game_loop.cancel(Timer::Demo);
+    }
+
+    fn step_demo(&mut self) -> bool {
+        let rules = self.settings.mode.rules();
+        let demo = match &mut self.demo {
+            Some(demo) => demo,
+            None => return false,
+        };
+        let (best, ms) = perf::timed(|| self.settings.solver.solver().best_move(&demo.board, rules));
+        self.perf.solved("演示", ms);
+        match best {
+            Some(direction) => {
+                demo.score += demo.board.slide(rules, direction).iter().map(Event::score).sum::<u64>();
+                self.settings.spawn_policy.spawn(&mut demo.board, rules, &mut demo.rng);
+                demo.moves += 1;
+                let won = self.settings.mode.stops_on_win() && rules.wins(demo.board.highest());
+                demo.finished = won || demo.moves >= DEMO_MOVE_LIMIT;
+            }
+            None => demo.finished = true,
+        }
+        if !demo.finished {
+            self.game_loop.schedule(Timer::Demo, DEMO_STEP_MS);
+        }
+        true
+    }
+
+    fn refresh_storage(&mut self, ctx: &Context<Self>) {
+        self.storage_usage = storage::local_usage();
+        ctx.link().send_future(async { Msg::StorageEstimated(storage::estimate().await) });
+    }
+
+    fn delete_stored(&mut self, ctx: &Context<Self>, key: &str) {
+        storage::delete(key);
+        self.reload_stored(ctx);
+    }
+
+    // Whatever is in storage now replaces what was loaded at startup; the
+    // game on the board carries on.
+    fn reload_stored(&mut self, ctx: &Context<Self>) {
+        self.leaderboard = self.load_leaderboard();
+        self.player_name = leaderboard::player_name(&self.namespace);
+        self.achievements = Achievements::load(&self.namespace);
+        self.records = Records::load(&self.namespace);
+        self.totals = Totals::load(&self.namespace);
+        self.ghosts = Runs::load(&self.namespace);
+        self.daily = Daily::load(&self.namespace);
+        self.settings = Settings::load(&self.namespace);
+        if self.set_aside.is_some() {
+            self.set_aside = Some(self.take_puzzle_rules());
+        }
+        self.flags = Flags::load(&self.namespace);
+        self.apply_flags(ctx);
+        self.refresh_storage(ctx);
+    }
+
+    fn intro_diagonals(&self) -> u8 {
+        self.board.rows() + self.board.columns() - 1
+    }
+
+    fn start_intro(&mut self) {
+        if self.animates() {
+            self.intro = Some(0);
+            self.game_loop.schedule(Timer::Intro, INTRO_STEP_MS);
+        }
+    }
+
+    // The last step holds while the tiles pop, then the intro is over.
+    fn step_intro(&mut self) -> bool {
+        let diagonals = self.intro_diagonals();
+        self.intro = match self.intro {
+            Some(step) if step < diagonals => Some(step + 1),
+            _ => None,
+        };
+        if let Some(step) = self.intro {
+            self.game_loop.schedule(Timer::Intro, if step == diagonals { ANIMATION_MS } else { INTRO_STEP_MS });
+        }
+        true
+    }
+
+    fn cancel_animation(&mut self) {
+        self.intro = None;
+        self.game_loop.cancel(Timer::Intro);
+        self.game_loop.cancel(Timer::Animation);
+        self.game_loop.cancel(Timer::Popups);
+        self.turn = MoveResult::default();
+        self.popups.clear();
+        self.pending_moves.clear();
+    }
+
+    fn snapshot(&self) -> Snapshot {
+        Snapshot {
+            board: self.board,
+            phase: self.phase,
+            score: self.score,
+            moves: self.moves,
+            replay_hash: self.replay_hash,
+            stats: self.stats.clone(),
+            rng: self.rng,
+            powerups: self.powerups,
+            played: self.played,
+        }
+    }
+
+    fn restore(&mut self, snapshot: Snapshot) {
+        self.board = snapshot.board;
+        self.phase = snapshot.phase;
+        self.score = snapshot.score;
+        self.moves = snapshot.moves;
+        self.replay_hash = snapshot.replay_hash;
+        self.stats = snapshot.stats;
+        self.rng = snapshot.rng;
+        self.powerups = snapshot.powerups;
+        self.played = snapshot.played;
+    }
+
+    // Any new change makes the undone moves unreachable.
+    fn push_history(&mut self) {
+        self.redo.clear();
+        self.record_history();
+    }
+
+    fn record_history(&mut self) {
+        self.history.push(self.snapshot());
+        self.trim_history();
+        if !self.settings.low_memory {
+            self.score_history.push(self.score);
+        }
+    }
+
+    fn trim_history(&mut self) {
+        let limit = if self.settings.low_memory { LOW_MEMORY_HISTORY_LIMIT } else { HISTORY_LIMIT };
+        if self.history.len() > limit {
+            self.history.drain(..self.history.len() - limit);
+        }
+        if self.settings.low_memory {
+            self.score_history = Vec::new();
+        }
+    }
+
+    fn undo(&mut self) -> bool {
+        self.cancel_animation();
+        self.close_continuation();
+        match self.history.pop() {
+            Some(snapshot) => {
+                self.redo.push(self.snapshot());
+                self.restore(snapshot);
+                self.analysis.forget_after(self.moves);
+                self.score_history.pop();
+                self.finished_at = None;
+                self.undos += 1;
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn redo(&mut self) -> bool {
+        self.cancel_animation();
+        self.close_continuation();
+        match self.redo.pop() {
+            Some(snapshot) => {
+                self.record_history();
+                self.restore(snapshot);
+                if self.over() {
+                    self.finished_at = Some(js_sys::Date::now());
+                }
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn save_settings(&self) {
+        match self.set_aside {
+            Some((mode, spawn_policy)) => {
+                let mut settings = self.settings.clone();
+                (settings.mode, settings.spawn_policy) = (mode, spawn_policy);
+                settings.save();
+            }
+            None => self.settings.save(),
+        }
+    }
+
+    fn restart(&mut self, seed: Option<u64>) {
+        if let Some((mode, spawn_policy)) = self.set_aside.take() {
+            self.settings.mode = mode;
+            self.settings.spawn_policy = spawn_policy;
+        }
+        self.cancel_animation();
+        self.close_continuation();
+        self.mood = Mood::default();
+        self.editor = None;
+        self.practice = false;
+        self.puzzle = None;
+        self.analysis.clear();
+        self.leaderboard = self.load_leaderboard();
+        // The date is pinned at the start, so a game that runs past midnight
+        // stays that day's challenge.
+        self.daily_date = self.settings.mode.daily().then(leaderboard::today);
+        self.seed = seed
+            .or(self.shared_seed)
+            .or_else(|| self.daily_date.as_deref().map(daily::seed))
+            .unwrap_or_else(Rng::random_seed);
+        // Race and online seeds are fresh every round, so only these can come round again.
+        self.seeded = seed.is_some() || self.daily_date.is_some();
+        self.counted = None;
+        self.ghost = match self.seeded && self.seat.is_none() {
+            true => self.ghosts.best(self.settings.mode, self.settings.spawn_policy, self.seed).cloned().map(Ghost::new),
+            false => None,
+        };
+        self.restore(Snapshot {
+            board: self.settings.mode.board(),
+            phase: GamePhase::Playing,
+            score: 0,
+            moves: 0,
+            replay_hash: global::REPLAY_HASH_START,
+            stats: Stats::default(),
+            rng: Rng::new(self.seed),
+            powerups: PowerUps::default(),
+            played: 0,
+        });
+        self.selecting = None;
+        self.announcement.clear();
+        self.started_at = js_sys::Date::now();
+        self.finished_at = None;
+        self.paused_at = None;
+        self.paused_total = 0.0;
+        self.history.clear();
+        self.redo.clear();
+        self.score_history.clear();
+        self.undos = 0;
+        self.steps.clear();
+        self.replayable = true;
+        self.playback.clear();
+        self.game_loop.cancel(Timer::Replay);
+        self.settings.mode.place_walls(&mut self.board, &mut self.rng);
+        self.replay_start = MoveResult::from_events(self.add_at_random_position()).spawned;
+        self.start_clock();
+        self.start_intro();
+    }
+
+    fn handoff(&self) -> Handoff {
+        Handoff {
+            mode: self.settings.mode,
+            cells: Handoff::cells(&self.board),
+            score: self.score,
+            moves: self.moves,
+            won: self.phase.reached_goal(),
+            is_dead: self.phase == GamePhase::Lost,
+            stats: self.stats.clone(),
+            seed: self.seed,
+            rng: self.rng,
+            powerups: self.powerups,
+            remaining: self.remaining,
+            expires: Handoff::expiry(js_sys::Date::now()),
+            daily_date: self.daily_date.clone(),
+            undos: self.undos,
+        }
+    }
+
+    fn apply_handoff(&mut self, code: &str) {
+        match Handoff::decode(code, js_sys::Date::now()) {
+            Ok(handoff) => self.pick_up(handoff),
+            Err(e) => self.handoff_error = Some(e.message()),
+        }
+    }
+
+    fn pick_up(&mut self, handoff: Handoff) {
+        self.set_aside = None;
+        self.settings.mode = handoff.mode;
+        self.save_settings();
+        self.restart(Some(handoff.seed));
+        self.restore(Snapshot {
+            board: handoff.board(),
+            phase: GamePhase::from_flags(handoff.won, handoff.is_dead),
+            score: handoff.score,
+            moves: handoff.moves,
+            replay_hash: global::REPLAY_HASH_START,
+            stats: handoff.stats,
+            rng: handoff.rng,
+            powerups: handoff.powerups,
+            played: 0,
+        });
+        self.replayable = false;
+        self.remaining = handoff.remaining;
+        self.undos = handoff.undos;
+        if handoff.daily_date.is_some() {
+            self.daily_date = handoff.daily_date;
+        }
+        if self.remaining.unwrap_or(0) == 0 {
+            self.game_loop.cancel(Timer::Clock);
+        }
+        self.show_handoff = false;
+        self.handoff_error = None;
+        self.autosave();
+    }
+
+    fn handoff_view(&self, ctx: &Context<Self>) -> Html {
+        if !self.show_handoff {
+            return html! {};
+        }
+
+        let onchange = ctx.link().callback(|event: yew::events::Event| {
+            Msg::ApplyHandoff(event.target_unchecked_into::<HtmlInputElement>().value())
+        });
+        html! {
+            <div class="handoff">
+            <h3>{ "换台设备接着玩" }</h3>
+            if let Some(code) = &self.handoff_code {
+                <p>{ "把这个接力码带到另一台设备上，30分钟内有效：" }</p>
+                <textarea readonly=true rows="4" cols="40" value={code.clone()} />
+            }
+            <label>{ "输入接力码：" }
+            <input type="text" {onchange} />
+            </label>
+            if let Some(error) = self.handoff_error {
+                <p class="warning">{ error }</p>
+            }
+            </div>
+        }
+    }
+
+    fn replay(&self) -> Option<Replay> {
+        (self.replayable && !self.practice && self.settings.mode != GameMode::Event).then(|| Replay {
+            mode: self.settings.mode,
+            spawn: self.settings.spawn_policy,
+            seed: self.seed,
+            start: self.replay_start,
+            steps: self.steps[..self.played].to_vec(),
+        })
+    }
+
+    // Checked in full before anything moves; playback is then a practice
+    // game stepped by a timer, through the same moves as live play.
+    fn play_replay(&mut self, text: &str) {
+        let replay = match Replay::parse(text) {
+            Ok(replay) => replay,
+            Err(e) => {
+                self.replay_error = Some(e.message());
+                return;
+            }
+        };
+        self.set_aside = None;
+        self.settings.mode = replay.mode;
+        self.settings.spawn_policy = replay.spawn;
+        self.save_settings();
+        self.restart(Some(replay.seed));
+        self.practice = true;
+        self.playback = replay.steps.iter().map(|step| step.direction).collect();
+        self.replay_error = None;
+        self.game_loop.schedule(Timer::Replay, DEMO_STEP_MS);
+    }
+
+    // Played under classic rules and uniform spawns from the puzzle's seed,
+    // whatever was picked before, like a replay.
+    fn start_puzzle(&mut self, i: usize) {
+        let board = match self.puzzles.get(i).and_then(Puzzle::board) {
+            Some(board) => board,
+            None => return,
+        };
+        let kept = self.set_aside.take().unwrap_or_else(|| self.take_puzzle_rules());
+        self.restart(Some(self.puzzles[i].seed));
+        self.set_aside = Some(kept);
+        let mut stats = Stats::default();
+        stats.record_tile(board.highest());
+        self.restore(Snapshot {
+            board,
+            phase: GamePhase::Playing.judged(board.dead(self.settings.mode.rules())),
+            score: 0,
+            moves: 0,
+            replay_hash: global::REPLAY_HASH_START,
+            stats,
+            rng: self.rng,
+            powerups: PowerUps::default(),
+            played: 0,
+        });
+        self.practice = true;
+        self.replayable = false;
+        self.seeded = false;
+        self.ghost = None;
+        self.puzzle = Some(i);
+        self.show_puzzles = false;
+    }
+
+    // Puzzles are written for the classic rules and uniform spawns. Gives
+    // back what the player had.
+    fn take_puzzle_rules(&mut self) -> (GameMode, SpawnPolicy) {
+        let kept = (self.settings.mode, self.settings.spawn_policy);
+        self.settings.mode = GameMode::Classic;
+        self.settings.spawn_policy = SpawnPolicy::Uniform;
+        kept
+    }
+
+    fn open_shared_puzzle(&mut self, code: &str) {
+        self.show_puzzles = true;
+        match Puzzle::decode(code) {
+            Ok(puzzle) => {
+                let i = self.puzzles.iter().position(|p| p.id == puzzle.id).unwrap_or_else(|| {
+                    self.puzzles.push(puzzle);
+                    self.puzzles.len() - 1
+                });
+                self.start_puzzle(i);
+            }
+            Err(e) => self.puzzle_error = Some(e.message().to_string()),
+        }
+    }
+
+    fn step_replay(&mut self) -> bool {
+        let direction = match self.playback.pop_front() {
+            Some(direction) => direction,
+            None => return false,
+        };
+        self.keep_playing();
+        self.apply_move(direction);
+        if !self.playback.is_empty() {
+            self.game_loop.schedule(Timer::Replay, DEMO_STEP_MS);
+        }
+        true
+    }
+
+    fn replay_view(&self, ctx: &Context<Self>) -> Html {
+        if !self.show_replay {
+            return html! {};
+        }
+
+        let link = ctx.link();
+        let onchange = link.callback(|event: yew::events::Event| Msg::ChooseReplay(event.target_unchecked_into::<HtmlInputElement>()));
+        html! {
+            <div class="replay">
+            <h3>{ "回放" }</h3>
+            if self.replay().is_some() {
+                <button onclick={link.callback(|_| Msg::DownloadReplay)}>{ "下载这局的回放" }</button>
+            } else {
+                <p>{ "这局用过道具、摆过盘、是接力来的或是活动模式，导不出回放" }</p>
+            }
+            <label>{ "加载回放文件：" }
+            <input type="file" accept=".json,application/json" {onchange} />
+            </label>
+            if !self.playback.is_empty() {
+                <p>{ format!("回放中，还剩{}步", self.playback.len()) }</p>
+            }
+            if let Some(error) = &self.replay_error {
+                <p class="warning">{ error }</p>
+            }
+            </div>
+        }
+    }
+
+    fn start_clock(&mut self) {
+        self.remaining = self.settings.mode.time_limit();
+        if self.remaining.is_some() {
+            self.game_loop.schedule(Timer::Clock, CLOCK_MS);
+        } else {
+            self.game_loop.cancel(Timer::Clock);
+        }
+    }
+
+    fn tick(&mut self) -> bool {
+        match self.remaining {
+            Some(remaining) if remaining > 0 => {
+                self.remaining = Some(remaining - 1);
+                if remaining == 1 {
+                    self.pending_moves.clear();
+                    if !self.phase.over() {
+                        self.finish();
+                    }
+                } else {
+                    self.game_loop.schedule(Timer::Clock, CLOCK_MS);
+                }
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn capture_binding(&mut self, key: &str) -> Option<bool> {
+        if self.rebinding.is_some() && MODIFIER_KEYS.contains(&key) {
+            return Some(false);
+        }
+        if let Some(action) = self.rebinding.take() {
+            if key != "Escape" {
+                self.settings.key_bindings.bind(action, key);
+                self.save_settings();
+            }
+            return Some(true);
+        }
+        None
+    }
+
+    fn handle_input(&mut self, raw: RawInput) -> bool {
+        if let RawInput::KeyDown(press) = &raw {
+            if let Some(changed) = self.capture_binding(&press.key) {
+                return changed;
+            }
+            // A key typed into a field is the field's: no debug panel, no board edits.
+            if press.editing {
+                return false;
+            }
+            if press.key == "`" {
+                self.show_debug = !self.show_debug;
+                return true;
+            }
+        }
+
+        let display = self.display();
+        if let Some(editor) = &mut self.editor {
+            return match &raw {
+                RawInput::KeyDown(press) => editor.key(&mut self.board, display, &press.key),
+                _ => false,
+            };
+        }
+        let input = self.input.dispatch(&raw, &self.settings, js_sys::Date::now());
+        // Shift with a move key shows the move instead of making it, until either is let go.
+        if let (RawInput::KeyDown(press), Some((Source::Keyboard, Action::Move(direction)))) = (&raw, input) {
+            if press.shift {
+                self.preview = Some(direction);
+                return true;
+            }
+        }
+        self.handle_action(input)
+    }
 
-impl Direction {
-    fn opposite(&self) -> Self {
-        match self {
-            Direction::Up => Direction::Down,
-            Direction::Down => Direction::Up,
-            Direction::Left => Direction::Right,
-            Direction::Right => Direction::Left,
-        } 
+    fn handle_action(&mut self, input: Option<(Source, Action)>) -> bool {
+        if self.confirming.is_some() {
+            return false;
+        }
+        if self.paused_at.is_some() {
+            return match input {
+                Some((_, Action::Pause)) => self.resume(),
+                Some((_, Action::ReadBoard)) => self.read_board(),
+                _ => false,
+            };
+        }
+        // Scripted: only the move the tutorial asks for, once the last one has landed.
+        if let Some(forced) = self.tutorial.map(|tutorial| tutorial.forced()) {
+            return match input {
+                Some((_, Action::Move(direction))) if forced == Some(direction) && !self.game_loop.pending(Timer::Animation) => {
+                    self.handle_move(direction)
+                }
+                _ => false,
+            };
+        }
+        match input {
+            Some((source, Action::Move(direction))) => {
+                if source == Source::Keyboard {
+                    self.start_turbo(direction);
+                }
+                self.handle_move(direction)
+            }
+            Some((_, Action::Undo)) => self.undo(),
+            Some((_, Action::Redo)) => self.redo(),
+            Some((_, Action::Restart)) => self.confirm(Confirmation::Restart),
+            Some((_, Action::Pause)) => self.pause(),
+            Some((_, Action::ReadBoard)) => self.read_board(),
+            None => false,
+        }
     }
 
-    fn perpendicular_positive(&self) -> Self {
-        match self {
-            Direction::Up => Direction::Left,
-            Direction::Left => Direction::Down,
-            Direction::Down => Direction::Right,
-            Direction::Right => Direction::Up,
+    // Only a game with something to lose asks first.
+    fn confirm(&mut self, confirmation: Confirmation) -> bool {
+        if self.score > 0 && !self.over() {
+            self.confirming = Some(confirmation);
+        } else {
+            self.carry_out(confirmation);
         }
+        true
     }
-}
 
-impl Position {
-    fn position(self) -> usize {
-        (6 * self.row + self.column) as usize
+    fn carry_out(&mut self, confirmation: Confirmation) {
+        if let Confirmation::Mode(mode) = confirmation {
+            self.set_aside = None;
+            self.settings.mode = mode;
+            self.save_settings();
+        }
+        self.restart(None);
     }
-}
 
-impl Index<Position> for GameState {
-    type Output = u64;
+    fn confirm_view(&self, ctx: &Context<Self>) -> Html {
+        let message = match self.confirming {
+            Some(Confirmation::Restart) => format!("这局还没完，{}分不要了？", self.score),
+            Some(Confirmation::Mode(mode)) => format!("换成{}就得重开，这局{}分不要了？", mode.label(), self.score),
+            None => return html! {},
+        };
+        let props = ConfirmProps { message, yes: "重开", no: "接着玩", onanswer: ctx.link().callback(Msg::Answer) };
+        html! { <Confirm ..props /> }
+    }
 
-    fn index(&self, i: Position) -> &u64 {
-        if i.row > 5 || i.column > 5 {
-            panic!("Index out of bound!");
+    // Same rules as the editor: an edit is one undo step and makes the game practice.
+    fn set_inspected(&mut self, p: Position, cell: Cell) {
+        if self.board[p] == cell {
+            return;
         }
-        &self.state[i.position()]
+        self.push_history();
+        self.board.set(p.position(), cell);
+        self.practice = true;
+        self.phase = self.phase.judged(self.board.dead(self.settings.mode.rules()));
     }
-}
 
-impl IndexMut<Position> for GameState {
-    fn index_mut(&mut self, i: Position) -> &mut u64 {
-        if i.row > 5 || i.column > 5 {
-            panic!("Index out of bound!");
+    // Edited games are practice: they never reach the leaderboard, the daily
+    // record or the achievements, and play resumes from whatever was set up.
+    fn toggle_editor(&mut self) {
+        if self.editor.take().is_some() {
+            self.practice = true;
+            self.phase = GamePhase::Playing.judged(self.board.dead(self.settings.mode.rules()));
+            self.finished_at = None;
+            if self.remaining.is_some_and(|r| r > 0) {
+                self.game_loop.schedule(Timer::Clock, CLOCK_MS);
+            }
+            return;
         }
+        self.stop_turbo();
+        self.cancel_animation();
+        self.close_continuation();
+        self.selecting = None;
+        self.push_history();
+        self.game_loop.cancel(Timer::Clock);
+        self.phase = GamePhase::Playing;
+        self.editor = Some(Editor::default());
+    }
 
-        &mut self.state[i.position()]
+    fn keep_playing(&mut self) -> bool {
+        if self.phase != GamePhase::Won {
+            return false;
+        }
+        self.phase = GamePhase::WonContinuing;
+        self.finished_at = None;
+        if self.remaining.is_some_and(|r| r > 0) {
+            self.game_loop.schedule(Timer::Clock, CLOCK_MS);
+        }
+        true
     }
-}
 
-impl Position {
-    fn neibouring_cell(self, pointing: Direction) -> Option<Position> {
-        match pointing {
-            Direction::Up => if self.row == 0 { None } else { Some(Position{row: self.row - 1, column: self.column}) },
-            Direction::Down => if self.row == 5 { None } else { Some(Position{row: self.row + 1, column: self.column}) },
-            Direction::Left => if self.column == 0 { None } else { Some(Position{row: self.row, column: self.column - 1}) },
-            Direction::Right => if self.column == 5 { None } else { Some(Position{row: self.row, column: self.column + 1}) },
+    fn pause(&mut self) -> bool {
+        if self.paused_at.is_some() || self.over() {
+            return false;
         }
+        self.paused_at = Some(js_sys::Date::now());
+        self.game_loop.cancel(Timer::Clock);
+        self.stop_turbo();
+        self.pending_moves.clear();
+        self.selecting = None;
+        true
     }
 
-    fn from_index(index: u64) -> Self {
-        Self {
-            row: (index / 6) as u8,
-            column: (index % 6) as u8,
+    fn resume(&mut self) -> bool {
+        let paused_at = match self.paused_at.take() {
+            Some(paused_at) => paused_at,
+            None => return false,
+        };
+        self.paused_total += js_sys::Date::now() - paused_at;
+        if self.remaining.is_some_and(|r| r > 0) {
+            self.game_loop.schedule(Timer::Clock, CLOCK_MS);
         }
+        true
+    }
+
+    // On demand and whatever the narration setting: printed to the live region
+    // and spoken in the narration language.
+    fn read_board(&mut self) -> bool {
+        let display = self.display();
+        self.announcement = narration::board_written(&self.board, display);
+        let locale = self.settings.narration_locale;
+        narration::speak(&narration::board_spoken(&self.board, display, locale), locale, self.settings.narration_voice.as_deref());
+        true
+    }
+
+    fn listen_visibility(ctx: &Context<Self>) -> Option<EventListener> {
+        web_sys::window().and_then(|w| w.document()).map(|document| {
+            let link = ctx.link().clone();
+            EventListener::new(&document, "visibilitychange", move |_| link.send_message(Msg::VisibilityChanged))
+        })
     }
-}
 
-impl Iterator for LineIteration {
-    type Item = Position;
+    fn listen_keys(ctx: &Context<Self>) -> Option<EventListener> {
+        web_sys::window().map(|window| {
+            let link = ctx.link().clone();
+            EventListener::new(&window, "keydown", move |event| {
+                if let Some(event) = event.dyn_ref::<KeyboardEvent>().filter(|event| !event.repeat()) {
+                    link.send_message(Msg::Input(RawInput::KeyDown(KeyPress::from_event(event))));
+                }
+            })
+        })
+    }
+
+    fn report(&self) -> Report {
+        Report { board: self.board, score: self.score, won: self.phase.reached_goal(), over: self.over(), attacks: self.attacks }
+    }
 
-    fn next(&mut self) -> Option<Position> {
-        if self.ended {
-            return None;
+    // Junk lands on its own rng so the seat's spawns stay in step with the
+    // other board. It can be what fills the board up.
+    fn take_garbage(&mut self) {
+        let empties = self.board.empties();
+        if self.over() || empties.is_empty() {
+            return;
+        }
+        let position = Position::from_index(empties[self.garbage_rng.below(empties.len())]);
+        self.apply_event(Event::Spawn { position, value: race::JUNK });
+        self.replayable = false;
+        if !self.board.dead(self.settings.mode.rules()) {
+            return;
+        }
+        // Junk ends a race the way a move would: endless boards clear a row.
+        if self.settings.mode.endless() {
+            self.clear_weakest_row();
+            return;
+        }
+        self.phase = GamePhase::Lost;
+        if self.settings.mascot {
+            self.mood = Mood::Sad;
         }
+        if self.settings.haptics {
+            haptics::game_over();
+        }
+        self.finish();
+    }
+
+    fn listen_resize(ctx: &Context<Self>) -> Option<EventListener> {
+        web_sys::window().map(|window| {
+            let link = ctx.link().clone();
+            EventListener::new(&window, "resize", move |_| link.send_message(Msg::Resized))
+        })
+    }
+
+    // The tab is going away, maybe mid-animation: whatever the last timer
+    // didn't catch is written now.
+    fn listen_unload(ctx: &Context<Self>) -> Option<EventListener> {
+        web_sys::window().map(|window| {
+            let link = ctx.link().clone();
+            EventListener::new(&window, "beforeunload", move |_| link.send_message(Msg::Autosave))
+        })
+    }
 
-        let temp = self.head;
-        if let Some(next) = self.head.neibouring_cell(self.direction) {
-            self.head = next;
+    // Every move writes the game; the timer, hiding the tab and closing it
+    // pick up everything else. Race seats, puzzles, practice and replays are
+    // never resumed, and a finished game has nothing to resume.
+    fn autosave(&mut self) {
+        if self.seat.is_some() || self.puzzle.is_some() || self.practice || self.editor.is_some() || !self.playback.is_empty() {
+            return;
+        }
+        if self.over() {
+            self.autosave.clear();
         } else {
-            self.ended = true;
+            self.autosave.save(&Handoff { expires: 0.0, ..self.handoff() });
         }
+    }
 
-        Some(temp)
+    fn listen_online(ctx: &Context<Self>) -> Option<EventListener> {
+        web_sys::window().map(|window| {
+            let link = ctx.link().clone();
+            EventListener::new(&window, "online", move |_| link.send_message(Msg::Online))
+        })
     }
-}
 
-impl LineIteration {
-    fn heads(direction: Direction) -> Self {
-        let start = match direction {
-            Direction::Up => Position{row: 0, column: 5},
-            Direction::Down => Position{row: 5, column: 0},
-            Direction::Left => Position{row: 0, column: 0},
-            Direction::Right => Position{row: 5, column: 5},
+    // Left queued while there's nowhere to send them.
+    fn resend_queued(&mut self) {
+        if self.settings.global_endpoint().is_some() {
+            self.submissions.extend(global::take_queued(&self.namespace));
+        }
+    }
+
+    fn send_submissions(&mut self, ctx: &Context<Self>) {
+        let endpoint = match self.settings.global_endpoint() {
+            Some(endpoint) => endpoint,
+            None => return,
         };
+        for submission in self.submissions.drain(..) {
+            let endpoint = endpoint.clone();
+            ctx.link().send_future(async move {
+                let result = global::submit(endpoint, submission.clone()).await;
+                Msg::GlobalSubmitted(result.map_err(|e| (Box::new(submission), e)))
+            });
+        }
+    }
 
-        Self {head: start, direction: direction.perpendicular_positive(), ended: false}
+    fn start_turbo(&mut self, direction: Direction) {
+        if !self.settings.turbo {
+            return;
+        }
+        self.turbo = Some(direction);
+        self.game_loop.schedule(Timer::Turbo, self.settings.turbo_interval_ms);
     }
-}
 
-impl GameState {
-    fn cell(&self, x: Position) -> String {
-        let order = self[x];
+    fn stop_turbo(&mut self) {
+        self.turbo = None;
+        self.game_loop.cancel(Timer::Turbo);
+    }
 
-        if order == 0 {
-            "".to_string()
-        } else {
-            format!("{}", order)
+    fn release_key(&mut self, key: &str) -> bool {
+        let held = self.turbo.map(Action::Move);
+        if held.is_some() && self.settings.key_bindings.action(key) == held {
+            self.stop_turbo();
         }
+        let previewed = self.preview.map(Action::Move);
+        if previewed.is_some() && (key == "Shift" || self.settings.key_bindings.action(key) == previewed) {
+            self.preview = None;
+            return true;
+        }
+        false
     }
 
-    fn dead(&self) -> bool {
-        for i in 0..36 {
-            let p = Position::from_index(i);
-            if self[p] == 0 {
-                return false;
-            }
-            if let Some(j) = p.neibouring_cell(Direction::Up) {
-                if self.mergeable(p, j) {
-                    return false
+    fn fire(&mut self, timer: Timer) -> bool {
+        match timer {
+            Timer::Clock => self.tick(),
+            // A queued move that no longer slides anything is skipped for the next one.
+            Timer::Animation => loop {
+                match self.pending_moves.pop_front() {
+                    Some(direction) if self.apply_move(direction) => break true,
+                    Some(_) => {}
+                    // The overlay waited for the animation to finish.
+                    None => break self.over(),
                 }
+            },
+            Timer::Toast => {
+                self.toasts.pop_front();
+                self.schedule_toast();
+                true
             }
-            if let Some(j) = p.neibouring_cell(Direction::Down) {
-                if self.mergeable(p, j) {
-                    return false
-                }
+            Timer::Demo => self.step_demo(),
+            Timer::Replay => self.step_replay(),
+            Timer::Popups => {
+                self.popups.clear();
+                true
             }
-            if let Some(j) = p.neibouring_cell(Direction::Left) {
-                if self.mergeable(p, j) {
-                    return false
-                }
+            Timer::Intro => self.step_intro(),
+            Timer::Autosave => {
+                self.autosave();
+                false
             }
-            if let Some(j) = p.neibouring_cell(Direction::Right) {
-                if self.mergeable(p, j) {
-                    return false
+            Timer::Tournament => match &mut self.tournament {
+                Some(tournament) => {
+                    tournament.advance(js_sys::Date::now() + TOURNAMENT_SLICE_MS);
+                    if tournament.running() {
+                        self.game_loop.schedule(Timer::Tournament, TOURNAMENT_STEP_MS);
+                    }
+                    true
                 }
+                None => false,
+            },
+            Timer::Analysis => {
+                if !self.settings.idle_analysis || !self.idle() {
+                    return false;
+                }
+                self.analysis.advance(self.settings.mode.rules()) && self.over()
             }
+            Timer::Turbo => match self.turbo {
+                Some(direction) => {
+                    self.game_loop.schedule(Timer::Turbo, self.settings.turbo_interval_ms);
+                    self.handle_move(direction)
+                }
+                None => false,
+            },
         }
+    }
 
-        true
+    fn gamepad_active(&self) -> bool {
+        self.gamepad_listener.is_some() && gamepad::any_connected()
     }
 
-    fn wins(&self) -> bool {
-        for i in 0..36 {
-            if self.state[i] >= 2048 {
-                return true
+    fn run_frame(&mut self, now: f64) -> bool {
+        self.frame = None;
+        let mut changed = false;
+        for _ in 0..self.game_loop.frame(now) {
+            for timer in self.game_loop.step() {
+                changed |= self.fire(timer);
+            }
+            if self.gamepad_active() {
+                changed |= self.handle_input(RawInput::GamepadFrame(self.game_loop.now()));
             }
         }
-
-        false
+        changed
     }
 
-    fn shitword(&self) -> &'static str {
-        if self.won {
-            return "你nb。然鹅想重新开始？并没有实现呢，刷新吧。"
+    fn ensure_frame(&mut self, ctx: &Context<Self>) {
+        if self.frame.is_some() {
+            return;
         }
-        if self.is_dead {
-            return "你寄了。想重新开始？然鹅并没有实现呢，刷新吧。";
+        if self.game_loop.idle() && !self.gamepad_active() {
+            self.game_loop.suspend();
+            return;
         }
+        let link = ctx.link().clone();
+        self.frame = Some(request_animation_frame(move |now| link.send_message(Msg::Frame(now))));
+    }
 
-        "按E/S/D/F操作晓得的不咯？"
+    // With several boards mounted, only reclaim focus from our own controls or
+    // the page body, never from another instance.
+    fn may_take_focus(&self) -> bool {
+        let active = web_sys::window()
+            .and_then(|w| w.document())
+            .and_then(|d| d.active_element());
+        let root = self.root.cast::<Element>();
+        match (active, root) {
+            (Some(active), Some(root)) => {
+                active.tag_name() == "BODY" || root.contains(Some(&active))
+            }
+            _ => true,
+        }
     }
 
-    fn add_at_random_position(&mut self) {
-        let empties: Vec<usize> = self.state.iter().enumerate().map(|s| {if *s.1 == 0u64 {Some(s.0)} else {None}}).filter(|s| {s.is_some()}).map(|s| {s.unwrap()}).collect();
+    fn focus(&self) {
+        if let Some(element) = self.gameplay.cast::<HtmlElement>() {
+            if let Err(e) = element.focus() {
+                log::warn!("Failed to focus the board: {:?}", e);
+            }
+        }
+    }
 
-        if empties.len() == 0 {
-            return;
+    fn settings_view(&self, ctx: &Context<Self>) -> Html {
+        if !self.show_settings {
+            return html! {};
         }
 
-        let mut buffer = [0u8; 1];
-        getrandom::getrandom(&mut buffer).unwrap();
-        let number = buffer[0] as usize % empties.len();
-        self.state[empties[number]] = 1;
+        let link = ctx.link();
+        self.settings.view(self.rebinding, link.callback(Msg::Rebind), link.callback(|_| Msg::ResetKeyBindings), link.callback(Msg::ChangeSettings))
+    }
+
+    fn listen_gamepad(ctx: &Context<Self>) -> Option<EventListener> {
+        web_sys::window().map(|window| {
+            let link = ctx.link().clone();
+            EventListener::new(&window, "gamepadconnected", move |_| link.send_message(Msg::GamepadConnected))
+        })
     }
 
-    fn mergeable(&self, x: Position, y: Position) -> bool {
-        (self[x] != 0) && (self[y] != 0) && (self[x] == self[y])
+    fn toggle_flag(&mut self, ctx: &Context<Self>, flag: Flag) {
+        self.flags.toggle(flag);
+        self.apply_flags(ctx);
     }
 
-    fn aggregate(&mut self, head: Position, direction: Direction) {
-        let mut write = head;
-        let mut count = 0;
+    fn apply_flags(&mut self, ctx: &Context<Self>) {
+        if !self.flags.enabled(Flag::Gamepad) {
+            self.gamepad_listener = None;
+        } else if self.gamepad_listener.is_none() {
+            self.gamepad_listener = Self::listen_gamepad(ctx);
+        }
+        if !self.flags.enabled(Flag::Animations) {
+            self.game_loop.cancel(Timer::Animation);
+            self.pending_moves.clear();
+        }
+    }
 
-        let elements = LineIteration {head, direction: direction.opposite(), ended: false};
-        for p in elements {
-            if self[p] == 0 {
-                continue;
-            }
-            if count == 0 {
-                self[write] = self[p];
-                count = 1;
-                continue;
-            }
-            if count == 1 {
-                if self.mergeable(write, p) {
-                    self[write] += self[p];
-                    write = write.neibouring_cell(direction.opposite()).unwrap();
-                    count = 0;
-                } else {
-                    write = write.neibouring_cell(direction.opposite()).unwrap();
-                    self[write] = self[p];
-                    count = 1;
-                }
-            }
+    fn debug_view(&self, ctx: &Context<Self>) -> Html {
+        if !self.show_debug {
+            return html! {};
         }
 
-        let remaining = if count == 0 {
-            LineIteration {head: write, direction: direction.opposite(), ended: false}
-        } else {
-            if let Some(next) = write.neibouring_cell(direction.opposite()) {
-                LineIteration {head: next, direction: direction.opposite(), ended: false}
-            } else {
-                LineIteration {head: write, direction: direction.opposite(), ended: true}
-            }
-        };
-        for p in remaining {
-            self[p] = 0;
+        let link = ctx.link();
+        html! {
+            <>
+            { self.flags.view(link.callback(Msg::ToggleFlag), link.callback(|_| Msg::ResetFlags)) }
+            { perf::toggle(self.perf.shown, link.callback(|_| Msg::TogglePerf)) }
+            { bench::view(&self.bench, link.callback(|_| Msg::RunBench)) }
+            { debugger::view(
+                Panel { board: &self.board, turn: &self.turn, seed: self.seed, rng: self.rng, stepper: self.stepper.as_ref() },
+                link.callback(Msg::StartStepping),
+                link.callback(|_| Msg::StepLine),
+                link.callback(|_| Msg::StopStepping),
+            ) }
+            </>
         }
     }
 
-    fn update_state(&mut self, direction: Direction) {
-        let heads = LineIteration::heads(direction);
-        for head in heads {
-            self.aggregate(head, direction);
+    fn showing_global(&self) -> bool {
+        self.show_global && self.settings.global_endpoint().is_some() && matches!(self.global, Some(Ok(_)))
+    }
+
+    // Anything short of a fetched list falls back to the local board below.
+    fn global_view(&self) -> Html {
+        if !self.show_global || self.settings.global_endpoint().is_none() {
+            return html! {};
         }
+        match &self.global {
+            None => html! { <p>{ "全球榜加载中…" }</p> },
+            Some(Ok(entries)) if entries.is_empty() => html! { <p>{ "全球榜还没人上榜" }</p> },
+            Some(Ok(entries)) => global::view(entries),
+            Some(Err(_)) => html! { <p class="warning">{ "连不上全球榜，先看本地的" }</p> },
+        }
+    }
 
-        if self.wins() {
-            self.won = true;
-            return;
+    fn leaderboard_view(&self, ctx: &Context<Self>) -> Html {
+        if !self.show_leaderboard || !self.settings.online() {
+            return html! {};
         }
 
-        self.add_at_random_position();
+        let link = ctx.link();
+        let onchange = link.callback(|event: yew::events::Event| {
+            Msg::SetPlayerName(event.target_unchecked_into::<HtmlInputElement>().value())
+        });
 
-        if self.dead() {
-            self.is_dead = true;
-            return;
+        html! {
+            <div>
+            <h3>{ "高分榜" }</h3>
+            <label>{ "名字：" }
+            <input type="text" maxlength={leaderboard::NAME_LENGTH.to_string()} value={self.player_name.clone()} {onchange} />
+            </label>
+            if self.settings.global_endpoint().is_some() {
+                <p>
+                <button disabled={!self.show_global} onclick={link.callback(|_| Msg::ShowGlobal(false))}>{ "本地" }</button>
+                <button disabled={self.show_global} onclick={link.callback(|_| Msg::ShowGlobal(true))}>{ "全球" }</button>
+                </p>
+            }
+            { self.global_view() }
+            if !self.showing_global() {
+                { self.leaderboard.view(link.callback(|_| Msg::ClearLeaderboard)) }
+            }
+            </div>
+        }
+    }
+
+    // Taking a move back, starting over or swapping in another board would
+    // spoil a race; seats don't show the buttons and don't take the messages.
+    fn spoils_race(msg: &Msg) -> bool {
+        matches!(
+            msg,
+            Msg::Undo | Msg::Redo | Msg::Restart | Msg::ToggleHint | Msg::ToggleEditor | Msg::ToggleHandoff | Msg::ToggleReplay | Msg::TogglePuzzles
+        )
+    }
+
+    fn handle(&mut self, ctx: &Context<Self>, msg: Msg) -> bool {
+        if self.seat.is_some() && Self::spoils_race(&msg) {
+            return false;
+        }
+        match msg {
+            Msg::Input(raw) => self.handle_input(raw),
+            Msg::KeyReleased(key) => self.release_key(&key),
+            Msg::Preview(direction) => {
+                self.preview = direction;
+                true
+            }
+            Msg::PadDown(direction) => {
+                self.pad_pressed_at = js_sys::Date::now();
+                self.preview = Some(direction);
+                true
+            }
+            // A mouse keeps previewing while it hovers; a finger lifting ends it.
+            Msg::PadUp(direction, mouse) => {
+                if !mouse {
+                    self.preview = None;
+                }
+                let tapped = js_sys::Date::now() - self.pad_pressed_at < preview::LONG_PRESS_MS;
+                if tapped && self.editor.is_none() {
+                    self.handle_action(Some((Source::Touch, Action::Move(direction))));
+                }
+                true
+            }
+            Msg::Blurred => {
+                self.stop_turbo();
+                false
+            }
+            Msg::Refocus => {
+                self.focus();
+                false
+            }
+            Msg::Undo => self.paused_at.is_none() && self.undo(),
+            Msg::Redo => self.paused_at.is_none() && self.redo(),
+            Msg::Restart => self.confirm(Confirmation::Restart),
+            Msg::ToggleSettings => {
+                self.show_settings = !self.show_settings;
+                self.rebinding = None;
+                true
+            }
+            Msg::Rebind(action) => {
+                self.rebinding = Some(action);
+                true
+            }
+            Msg::ResetKeyBindings => {
+                self.settings.key_bindings = KeyBindings::default();
+                self.save_settings();
+                self.rebinding = None;
+                true
+            }
+            Msg::ChangeSettings(settings) => {
+                // The replay can only name one policy for the whole game.
+                if settings.spawn_policy != self.settings.spawn_policy {
+                    self.replayable = false;
+                    if let Some((_, spawn_policy)) = &mut self.set_aside {
+                        *spawn_policy = settings.spawn_policy;
+                    }
+                }
+                self.settings = settings;
+                self.save_settings();
+                self.armed = None;
+                self.stop_turbo();
+                self.trim_history();
+                true
+            }
+            Msg::ToggleLeaderboard => {
+                self.show_leaderboard = !self.show_leaderboard;
+                true
+            }
+            Msg::ShowGlobal(show) => {
+                self.show_global = show;
+                if let (true, Some(endpoint)) = (show, self.settings.global_endpoint()) {
+                    self.global = None;
+                    ctx.link().send_future(async { Msg::GlobalLoaded(global::top(endpoint).await) });
+                }
+                true
+            }
+            Msg::GlobalLoaded(top) => {
+                if let Err(e) = &top {
+                    log::warn!("Failed to fetch the global leaderboard: {}", e);
+                }
+                self.global = Some(top);
+                true
+            }
+            Msg::GlobalSubmitted(result) => match result {
+                Ok(()) => false,
+                Err((submission, e)) => {
+                    log::warn!("Failed to submit to the global leaderboard: {}", e);
+                    global::queue(&self.namespace, *submission);
+                    self.toasts.push_back("没连上全球榜，联网后再交".to_string());
+                    self.schedule_toast();
+                    true
+                }
+            },
+            Msg::Online => {
+                self.resend_queued();
+                false
+            }
+            Msg::Autosave => {
+                self.autosave();
+                false
+            }
+            Msg::InstallAvailable(event) => {
+                self.install_prompt = Some(event);
+                true
+            }
+            Msg::Install => {
+                if let Some(event) = self.install_prompt.take() {
+                    pwa::prompt(&event);
+                }
+                true
+            }
+            Msg::Installed => {
+                self.install_prompt = None;
+                true
+            }
+            Msg::SetPlayerName(name) => {
+                self.player_name = name.trim().chars().take(leaderboard::NAME_LENGTH).collect();
+                leaderboard::set_player_name(&self.namespace, &self.player_name);
+                true
+            }
+            Msg::ClearLeaderboard => {
+                self.leaderboard.clear();
+                true
+            }
+            Msg::DismissChangelog => {
+                self.whats_new.clear();
+                changelog::mark_seen(&self.namespace);
+                true
+            }
+            Msg::EndTutorial => {
+                self.tutorial = None;
+                tutorial::finish(&self.namespace);
+                changelog::mark_seen(&self.namespace);
+                true
+            }
+            Msg::ToggleFlag(flag) => {
+                self.toggle_flag(ctx, flag);
+                true
+            }
+            Msg::ResetTotals => {
+                self.totals.reset();
+                true
+            }
+            Msg::ResetFlags => {
+                self.flags.reset();
+                self.apply_flags(ctx);
+                true
+            }
+            Msg::SetMode(mode) => self.confirm(Confirmation::Mode(mode)),
+            Msg::Answer(yes) => {
+                if let (Some(confirmation), true) = (self.confirming.take(), yes) {
+                    self.carry_out(confirmation);
+                }
+                true
+            }
+            Msg::SelectPowerUp(power_up) => {
+                if self.paused_at.is_none() {
+                    self.select_power_up(power_up);
+                }
+                true
+            }
+            Msg::CellClicked(p) => self.paused_at.is_none() && self.cell_clicked(p),
+            Msg::ToggleEditor => {
+                self.toggle_editor();
+                true
+            }
+            Msg::ToggleHint => {
+                self.hint = match self.hint {
+                    Some(_) => None,
+                    None => self.evaluate_hint(),
+                };
+                true
+            }
+            Msg::ReadBoard => self.read_board(),
+            Msg::Pause => self.pause(),
+            Msg::Resume => self.resume(),
+            Msg::Resized => {
+                let viewport = Viewport::current();
+                let rows = self.board.rows();
+                let columns = self.board.columns();
+                let changed = viewport.cell_size(rows, columns) != self.viewport.cell_size(rows, columns);
+                self.viewport = viewport;
+                changed
+            }
+            Msg::VisibilityChanged => {
+                let hidden = web_sys::window().and_then(|w| w.document()).map(|d| d.hidden()).unwrap_or(false);
+                if hidden {
+                    self.autosave();
+                }
+                hidden && self.pause()
+            }
+            Msg::SetInspected(cell) => match self.inspecting {
+                Some(p) => {
+                    self.set_inspected(p, cell);
+                    true
+                }
+                None => false,
+            },
+            Msg::CloseInspector => {
+                self.inspecting = None;
+                true
+            }
+            Msg::RunBench => {
+                self.bench = bench::run();
+                true
+            }
+            Msg::TogglePerf => {
+                self.perf.shown = !self.perf.shown;
+                true
+            }
+            Msg::StartStepping(direction) => {
+                self.stepper = Some(Stepper::new(self.board, direction));
+                true
+            }
+            Msg::StepLine => match &mut self.stepper {
+                Some(stepper) => {
+                    stepper.step(self.settings.mode.rules());
+                    true
+                }
+                None => false,
+            },
+            Msg::StopStepping => {
+                self.stepper = None;
+                true
+            }
+            Msg::ToggleTournament => {
+                self.tournament = match self.tournament {
+                    Some(_) => None,
+                    None => Some(Tournament::default()),
+                };
+                self.game_loop.cancel(Timer::Tournament);
+                true
+            }
+            Msg::SetPolicy((side, policy)) => {
+                if let Some(tournament) = &mut self.tournament {
+                    tournament.policies[side] = policy;
+                }
+                true
+            }
+            Msg::SetTournamentGames(games) => {
+                if let Some(tournament) = &mut self.tournament {
+                    tournament.games = games.clamp(1, 500);
+                }
+                true
+            }
+            Msg::StartTournament => {
+                if let Some(tournament) = &mut self.tournament {
+                    tournament.start(Rng::random_seed());
+                    self.game_loop.schedule(Timer::Tournament, TOURNAMENT_STEP_MS);
+                }
+                true
+            }
+            Msg::StopTournament => {
+                if let Some(tournament) = &mut self.tournament {
+                    tournament.stop();
+                }
+                self.game_loop.cancel(Timer::Tournament);
+                true
+            }
+            Msg::ToggleHandoff => {
+                self.show_handoff = !self.show_handoff;
+                self.handoff_error = None;
+                self.handoff_code = self.handoff().encode();
+                true
+            }
+            Msg::ApplyHandoff(code) => {
+                self.apply_handoff(&code);
+                true
+            }
+            Msg::ToggleHeatmap => {
+                self.show_heatmap = !self.show_heatmap;
+                true
+            }
+            Msg::TogglePuzzles => {
+                self.show_puzzles = !self.show_puzzles;
+                true
+            }
+            Msg::StartPuzzle(i) => {
+                self.start_puzzle(i);
+                true
+            }
+            Msg::EditDraft(draft) => {
+                self.draft = draft;
+                self.puzzle_link = None;
+                true
+            }
+            Msg::SharePuzzle => {
+                match Puzzle::from_board(&self.board, &self.draft, self.display(), Rng::random_seed()).encode() {
+                    Ok(code) => {
+                        self.puzzle_link = Some(puzzles::link(&code));
+                        self.puzzle_error = None;
+                    }
+                    Err(e) => {
+                        self.puzzle_link = None;
+                        self.puzzle_error = Some(e.message().to_string());
+                    }
+                }
+                true
+            }
+            Msg::ToggleReplay => {
+                self.show_replay = !self.show_replay;
+                true
+            }
+            Msg::DownloadReplay => {
+                if let Some(replay) = self.replay() {
+                    replay.download();
+                }
+                false
+            }
+            Msg::ChooseReplay(input) => {
+                self.replay_loader = replay::read(&input, ctx.link().callback(Msg::LoadReplay));
+                false
+            }
+            Msg::LoadReplay(text) => {
+                self.replay_loader = None;
+                self.play_replay(&text);
+                true
+            }
+            Msg::ToggleStorage => {
+                self.show_storage = !self.show_storage;
+                if self.show_storage {
+                    self.refresh_storage(ctx);
+                }
+                true
+            }
+            Msg::StorageEstimated(estimate) => {
+                self.storage_estimate = estimate;
+                true
+            }
+            Msg::DeleteStored(key) => {
+                self.delete_stored(ctx, &key);
+                true
+            }
+            Msg::ExportData => {
+                backup::export();
+                false
+            }
+            Msg::ChooseBackup(input) => {
+                self.backup_loader = replay::read(&input, ctx.link().callback(Msg::LoadBackup));
+                false
+            }
+            Msg::LoadBackup(text) => {
+                self.backup_loader = None;
+                self.imported = Some(backup::import(&text));
+                self.reload_stored(ctx);
+                true
+            }
+            Msg::DownloadSummary => {
+                if let Some(canvas) = self.summary_canvas.cast::<HtmlCanvasElement>() {
+                    summary::download(&canvas);
+                }
+                false
+            }
+            Msg::ShowContinuation => {
+                self.show_continuation();
+                true
+            }
+            Msg::CloseContinuation => {
+                self.close_continuation();
+                true
+            }
+            Msg::PlayAgain => {
+                self.restart(Some(self.seed));
+                true
+            }
+            Msg::KeepPlaying => self.keep_playing(),
+            Msg::GamepadConnected => false,
+            Msg::Frame(now) => self.run_frame(now),
         }
     }
 }
 
 impl Component for GameState {
-    type Message = Direction;
-    type Properties = ();
+    type Message = Msg;
+    type Properties = GameProps;
 
-    fn create(_ctx: &Context<Self>) -> Self {
+    fn create(ctx: &Context<Self>) -> Self {
+        let namespace = Namespace::new(&ctx.props().instance);
         let mut obj = Self {
-            state: [0; 36],
-            is_dead: false,
-            won: false,
+            board: Board::default(),
+            phase: GamePhase::Playing,
+            score: 0,
+            moves: 0,
+            replay_hash: global::REPLAY_HASH_START,
+            stats: Stats::default(),
+            leaderboard: Leaderboard::load(&namespace),
+            player_name: leaderboard::player_name(&namespace),
+            show_leaderboard: false,
+            show_heatmap: false,
+            preview: None,
+            pad_pressed_at: 0.0,
+            show_global: false,
+            global: None,
+            submissions: Vec::new(),
+            achievements: Achievements::load(&namespace),
+            records: Records::load(&namespace),
+            totals: Totals::load(&namespace),
+            solved_puzzles: Solved::load(&namespace),
+            counted: None,
+            toasts: VecDeque::new(),
+            input: InputPipeline::default(),
+            frame: None,
+            game_loop: GameLoop::default(),
+            gamepad_listener: None,
+            _visibility_listener: Self::listen_visibility(ctx),
+            viewport: Viewport::current(),
+            _resize_listener: Self::listen_resize(ctx),
+            _online_listener: Self::listen_online(ctx),
+            _unload_listener: ctx.props().seat.is_none().then(|| Self::listen_unload(ctx)).flatten(),
+            autosave: Autosave::new(&namespace),
+            _install_listeners: pwa::listen(ctx.link().callback(Msg::InstallAvailable), ctx.link().callback(|_| Msg::Installed)),
+            install_prompt: None,
+            history: Vec::new(),
+            redo: Vec::new(),
+            score_history: Vec::new(),
+            undos: 0,
+            settings: Settings::load(&namespace),
+            show_settings: false,
+            rebinding: None,
+            pending_moves: VecDeque::new(),
+            turn: MoveResult::default(),
+            popups: Vec::new(),
+            popup_round: 0,
+            intro: None,
+            audio: Audio::default(),
+            mood: Mood::default(),
+            daily: Daily::load(&namespace),
+            daily_date: None,
+            seeded: false,
+            ghosts: Runs::load(&namespace),
+            ghost: None,
+            editor: None,
+            practice: false,
+            season: seasons::active(&leaderboard::today()),
+            hint: None,
+            tournament: None,
+            bench: Vec::new(),
+            inspecting: None,
+            analysis: Analysis::default(),
+            last_move_at: 0.0,
+            armed: None,
+            announcement: String::new(),
+            last_move: MoveSummary::default(),
+            turbo: None,
+            whats_new: changelog::unseen(&namespace),
+            tutorial: None,
+            confirming: None,
+            flags: Flags::load(&namespace),
+            show_debug: flags::debug_requested(),
+            perf: Perf::default(),
+            stepper: None,
+            seed: 0,
+            shared_seed: ctx.props().seed,
+            seat: ctx.props().seat,
+            report: None,
+            outbox: Vec::new(),
+            attacks: 0,
+            garbage_taken: 0,
+            garbage_rng: Rng::new(!ctx.props().seed.unwrap_or_default()),
+            _key_listener: ctx.props().seat.and_then(|_| Self::listen_keys(ctx)),
+            rng: Rng::new(0),
+            remaining: None,
+            powerups: PowerUps::default(),
+            selecting: None,
+            show_storage: false,
+            storage_usage: Vec::new(),
+            storage_estimate: None,
+            demo: None,
+            namespace,
+            started_at: 0.0,
+            finished_at: None,
+            paused_at: None,
+            paused_total: 0.0,
+            summary_canvas: NodeRef::default(),
+            gameplay: NodeRef::default(),
+            root: NodeRef::default(),
+            show_handoff: false,
+            handoff_code: None,
+            handoff_error: None,
+            steps: Vec::new(),
+            played: 0,
+            replay_start: None,
+            replayable: true,
+            show_replay: false,
+            replay_error: None,
+            playback: VecDeque::new(),
+            replay_loader: None,
+            puzzles: puzzles::manifest(),
+            puzzle: None,
+            set_aside: None,
+            show_puzzles: false,
+            draft: Draft::default(),
+            puzzle_link: None,
+            puzzle_error: None,
+            backup_loader: None,
+            imported: None,
         };
-        obj.add_at_random_position();
+        obj.apply_flags(ctx);
+        if obj.settings.mode == GameMode::Event && obj.season.is_none() {
+            obj.settings.mode = GameMode::default();
+        }
+        // Not saved: the seat's storage keeps whatever the player chose outside races.
+        if let Some(seat) = obj.seat {
+            obj.input.set_shortcuts(false);
+            obj.settings.key_bindings = seat.key_bindings();
+            obj.settings.mode = GameMode::Classic;
+        }
+        obj.restart(None);
+        // Picks up where a closed or crashed tab left off, unless a link asked for a particular game.
+        if let (None, None, None) = (obj.seat, obj.shared_seed, flags::query_param("puzzle")) {
+            if let Some(saved) = obj.autosave.load() {
+                obj.pick_up(saved);
+                obj.toasts.push_back("接着上次没下完的那局".to_string());
+                obj.schedule_toast();
+            }
+        }
+        // ?puzzle= is a challenge someone made with 出题.
+        if let (None, Some(code)) = (obj.seat, flags::query_param("puzzle")) {
+            obj.open_shared_puzzle(&code);
+        }
+        // New players get the walkthrough instead of a changelog.
+        if obj.seat.is_none() && tutorial::first_launch(&obj.namespace) {
+            obj.tutorial = Some(Tutorial::start(&obj.board, obj.settings.mode.rules()));
+            obj.whats_new.clear();
+        }
+        obj.remind_daily();
+        obj.resend_queued();
+        obj.send_submissions(ctx);
+        obj.ensure_frame(ctx);
         log::info!("Created obj");
         obj
     }
 
     fn view(&self, ctx: &Context<Self>) -> Html {
         let link = ctx.link();
-        let onkeypress = link.batch_callback(|event: KeyboardEvent| {
-            match event.key().as_str() {
-                "E" | "e" => Some(Direction::Up),
-                "S" | "s" => Some(Direction::Left),
-                "D" | "d" => Some(Direction::Down),
-                "F" | "f" => Some(Direction::Right),
-                _ => None,
-            }
+        // Race seats hear the keyboard from the window instead, focused or not.
+        let seated = self.seat.is_some();
+        let onkeydown = link.batch_callback(move |event: KeyboardEvent| {
+            (!seated && !event.repeat()).then(|| Msg::Input(RawInput::KeyDown(KeyPress::from_event(&event))))
+        });
+        let ontouchstart = link.batch_callback(|event: TouchEvent| {
+            event.changed_touches().get(0).map(|t| Msg::Input(RawInput::TouchStart(t.client_x(), t.client_y())))
+        });
+        let ontouchend = link.batch_callback(|event: TouchEvent| {
+            event.changed_touches().get(0).map(|t| Msg::Input(RawInput::TouchEnd(t.client_x(), t.client_y())))
         });
+        let onkeyup = link.callback(|event: KeyboardEvent| Msg::KeyReleased(event.key()));
+        let onblur = link.callback(|_| Msg::Blurred);
+        let large_print = self.settings.large_print;
         
         html! {
-            <div tabindex="-1" id="gameplay" {onkeypress}>
-            <table>
-            { (0..6).map(|row| {
+            <div class={self.season.as_ref().map_or("game".to_string(), |s| format!("game {}", s.skin))} ref={self.root.clone()}>
+            <div tabindex="-1" ref={self.gameplay.clone()} class={if large_print { "gameplay large-print" } else { "gameplay" }}
+                onclick={link.callback(|_| Msg::Refocus)} {onkeydown} {onkeyup} {onblur} {ontouchstart} {ontouchend}>
+            { mode::view(self.settings.mode, self.season.as_ref(), link.callback(Msg::SetMode)) }
+            if let (Some(season), GameMode::Event) = (&self.season, self.settings.mode) {
+                <p class="season">{ format!("{}活动：{}，成绩单独排名", season.name, season.mutator.label()) }</p>
+            }
+            if let Some(date) = &self.daily_date {
+                { self.daily.view(date, self.display()) }
+            }
+            <p class="score">
+            { format!("分数：{}", self.score) }
+            { sparkline::view(&self.scores()) }
+            </p>
+            if self.settings.mascot {
+                { mascot::view(self.mood, self.moves, self.animates()) }
+            }
+            if let Some(remaining) = self.remaining {
+                <p class="clock">{ format!("剩余 {}:{:02}", remaining / 60, remaining % 60) }</p>
+            }
+            { self.powerups.view(self.selecting, link.callback(Msg::SelectPowerUp)) }
+            if self.editor.is_some() {
+                <p class="editor-hint">{ "摆盘中：点格子换数字，选中后也能直接打数字，W放障碍，退格清空。摆好点“开玩”。" }</p>
+            } else if let Some(i) = self.puzzle {
+                <p class="editor-hint">{ match self.puzzles[i].status(&self.board, self.moves) {
+                    Status::Solving { left } => format!("残局「{}」：{}，还剩{}步", self.puzzles[i].name, self.puzzles[i].objective(self.display()), left),
+                    _ => format!("残局「{}」：{}", self.puzzles[i].name, self.puzzles[i].objective(self.display())),
+                } }</p>
+            } else if self.practice {
+                <p class="editor-hint">{ "练习局，不计入高分榜和成就" }</p>
+            }
+            <div class="boards">
+            <div role="grid" aria-label="棋盘" class={if self.selecting.is_some() || self.editor.is_some() { "board selecting" } else { "board" }}
+                style={self.viewport.split(if seated { 2 } else { 1 }).board_style(self.board.rows(), self.board.columns())}>
+            { (0..self.board.rows()).map(|row| {
                 html! {
-                    <tr>
-                    { (0..6).map(|column| {
+                    <div role="row" class="row">
+                    { (0..self.board.columns()).map(|column| {
                         html! {
-                            <td class={format!("cell-{}", self[Position{row, column}])}>{ self.cell(Position{row, column}) }</td>
+                            <div role="gridcell" aria-label={self.cell_label(Position{row, column})}
+                                class={self.square_class(Position{row, column})}
+                                onclick={link.callback(move |_| Msg::CellClicked(Position{row, column}))}>
+                            { self.cell_heat(Position{row, column}) }
+                            </div>
                         }
                     }).collect::<Html>() }
-                    </tr>
+                    </div>
                 }
             }).collect::<Html>() }
-            </table>
+            { self.tiles() }
+            </div>
+            if let Some(ghost) = &self.ghost {
+                { ghost.view(self.settings.palette, self.display()) }
+            }
+            if let Some(direction) = self.preview {
+                { preview::view(&self.board, self.settings.mode.rules(), direction, self.settings.palette, self.display()) }
+            }
+            </div>
+            { preview::pad(link.callback(Msg::Preview), link.callback(Msg::PadDown), link.callback(|(direction, mouse)| Msg::PadUp(direction, mouse))) }
             <p>{ self.shitword() }</p>
+            <p class="sr-only" aria-live="polite">{ &self.announcement }</p>
+            if let Some(direction) = self.armed {
+                <p>{ format!("再按一次{}确认", Action::Move(direction).label()) }</p>
+            }
+            if !large_print {
+                <details>
+                <summary>{ "统计" }</summary>
+                { self.stats.view() }
+                { sparkline::graph(&self.scores()) }
+                { self.records.view() }
+                { self.totals.view(link.callback(|_| Msg::ResetTotals)) }
+                </details>
+                <details>
+                <summary>{ "成就" }</summary>
+                { self.achievements.view() }
+                </details>
+            }
+            { self.overlay(ctx) }
+            if let (true, Some(p)) = (self.show_debug, self.inspecting) {
+                { inspector::view(&self.board, p, link.callback(Msg::SetInspected), link.callback(|_| Msg::CloseInspector)) }
+            }
+            if self.paused_at.is_some() {
+                <div class="pause-overlay">
+                <p>{ format!("暂停中。按{}或点这里继续。", self.settings.key_bindings.key(Action::Pause)) }</p>
+                <button onclick={link.callback(|_| Msg::Resume)}>{ "继续" }</button>
+                </div>
+            }
+            </div>
+            if !seated {
+                <button onclick={link.callback(|_| Msg::Undo)}>{ "悔棋" }</button>
+                <button disabled={self.redo.is_empty()} onclick={link.callback(|_| Msg::Redo)}>{ "重做" }</button>
+                <button onclick={link.callback(|_| Msg::Restart)}>{ "重开" }</button>
+                <button onclick={link.callback(|_| Msg::ToggleHint)}>{ "提示" }</button>
+                <button onclick={link.callback(|_| Msg::ToggleEditor)}>{ if self.editor.is_some() { "开玩" } else { "摆盘" } }</button>
+            }
+            <button onclick={link.callback(|_| Msg::Pause)} disabled={self.paused_at.is_some() || self.over()}>{ "暂停" }</button>
+            <button onclick={link.callback(|_| Msg::ReadBoard)}>{ "读棋盘" }</button>
+            <button aria-pressed={self.show_heatmap.to_string()} onclick={link.callback(|_| Msg::ToggleHeatmap)}>{ "热力图" }</button>
+            if self.settings.online() {
+                <button onclick={link.callback(|_| Msg::ToggleLeaderboard)}>{ "高分榜" }</button>
+            }
+            <button onclick={link.callback(|_| Msg::ToggleSettings)}>{ "设置" }</button>
+            if self.install_prompt.is_some() && !seated {
+                <button onclick={link.callback(|_| Msg::Install)}>{ "安装到桌面" }</button>
+            }
+            <button onclick={link.callback(|_| Msg::ToggleStorage)}>{ "存储" }</button>
+            if !seated {
+                <button onclick={link.callback(|_| Msg::ToggleHandoff)}>{ "接力" }</button>
+                <button onclick={link.callback(|_| Msg::ToggleReplay)}>{ "回放" }</button>
+                <button onclick={link.callback(|_| Msg::TogglePuzzles)}>{ "残局" }</button>
+            }
+            <button onclick={link.callback(|_| Msg::ToggleTournament)}>{ "AI对战" }</button>
+            if let Some((_, evaluations)) = &self.hint {
+                { hint::view(evaluations) }
+            }
+            { self.leaderboard_view(ctx) }
+            { self.handoff_view(ctx) }
+            { self.replay_view(ctx) }
+            if self.show_puzzles {
+                { puzzles::view(
+                    &self.puzzles,
+                    &self.solved_puzzles,
+                    self.display(),
+                    Authoring { draft: &self.draft, link: self.puzzle_link.as_deref(), error: self.puzzle_error.as_deref() },
+                    link.callback(Msg::StartPuzzle),
+                    link.callback(Msg::EditDraft),
+                    link.callback(|_| Msg::SharePuzzle),
+                ) }
+            }
+            if let Some(tournament) = &self.tournament {
+                { tournament.view(
+                    link.callback(Msg::SetPolicy),
+                    link.callback(Msg::SetTournamentGames),
+                    link.callback(|_| Msg::StartTournament),
+                    link.callback(|_| Msg::StopTournament),
+                ) }
+            }
+            { self.settings_view(ctx) }
+            { self.debug_view(ctx) }
+            { self.perf.view() }
+            if self.show_storage {
+                { storage::view(&self.storage_usage, self.storage_estimate, link.callback(Msg::DeleteStored)) }
+                { backup::view(
+                    self.imported.as_ref(),
+                    link.callback(|_| Msg::ExportData),
+                    link.callback(|event: yew::events::Event| Msg::ChooseBackup(event.target_unchecked_into::<HtmlInputElement>())),
+                ) }
+            }
+            { self.confirm_view(ctx) }
+            if let Some(tutorial) = &self.tutorial {
+                { tutorial.view(&self.settings.key_bindings, link.callback(|_| Msg::EndTutorial)) }
+            }
+            if !large_print && !self.whats_new.is_empty() {
+                { changelog::view(&self.whats_new, link.callback(|_| Msg::DismissChangelog)) }
+            }
+            if !large_print {
+                if let Some(message) = self.toasts.front() {
+                    <Toast message={message.clone()} />
+                }
+            }
             </div>
         }
     }
 
-    fn update(&mut self, _ctx: &Context<Self>, msg: Self::Message) -> bool {
-        log::info!("Event: {:?}", msg);
-        if !self.is_dead && !self.won {
-            self.update_state(msg);
-            true
-        } else {
-            false
+    fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
+        let started = perf::now();
+        let changed = self.handle(ctx, msg);
+        // Only armed by a change, so an idle board lets the frame loop stop.
+        if changed && self.seat.is_none() && !self.game_loop.pending(Timer::Autosave) {
+            self.game_loop.schedule(Timer::Autosave, autosave::DELAY_MS);
+        }
+        self.send_submissions(ctx);
+        self.input.set_focus(self.input_focus());
+        self.refresh_hint();
+        if let Some(ghost) = &mut self.ghost {
+            ghost.seek(self.played);
         }
+        self.schedule_analysis();
+        self.ensure_frame(ctx);
+        self.perf.updated(perf::now() - started);
+        changed
     }
 
-    fn rendered(&mut self, _ctx: &Context<Self>, _first_render: bool) {
-        set_focus();
+    fn changed(&mut self, ctx: &Context<Self>) -> bool {
+        while self.garbage_taken < ctx.props().garbage {
+            self.garbage_taken += 1;
+            self.take_garbage();
+        }
+        true
+    }
+
+    fn rendered(&mut self, ctx: &Context<Self>, _first_render: bool) {
+        self.perf.rendered();
+        let report = self.report();
+        if self.report != Some(report) {
+            self.report = Some(report);
+            ctx.props().onreport.emit(report);
+        }
+        for turn in self.outbox.drain(..) {
+            ctx.props().onturn.emit(turn);
+        }
+        if let Some(canvas) = self.summary_canvas.cast::<HtmlCanvasElement>() {
+            summary::draw(&canvas, &self.summary());
+        }
+        // Any field inside the game counts, from the settings to the puzzle
+        // editor; the dialog keeps its own focus on its buttons.
+        let typing = input::field_focused() || self.confirming.is_some();
+        if !typing && self.may_take_focus() {
+            self.focus();
+        }
     }
 }
@@ -0,0 +1,94 @@
+use wasm_bindgen::JsCast;
+use web_sys::{Gamepad, GamepadButton};
+
+use crate::board::Direction;
+
+const DEADZONE: f64 = 0.5;
+const REPEAT_DELAY: f64 = 400.0;
+const REPEAT_INTERVAL: f64 = 150.0;
+
+const BUTTON_UP: u32 = 12;
+const BUTTON_DOWN: u32 = 13;
+const BUTTON_LEFT: u32 = 14;
+const BUTTON_RIGHT: u32 = 15;
+
+#[derive(Default)]
+pub struct GamepadPoller {
+    held: Option<Direction>,
+    next_repeat: f64,
+}
+
+impl GamepadPoller {
+    pub fn poll(&mut self, now: f64) -> Option<Direction> {
+        let direction = connected_gamepads().iter().find_map(read_direction);
+
+        match direction {
+            None => {
+                self.held = None;
+                None
+            }
+            Some(d) if self.held != Some(d) => {
+                self.held = Some(d);
+                self.next_repeat = now + REPEAT_DELAY;
+                Some(d)
+            }
+            Some(d) => {
+                if now >= self.next_repeat {
+                    self.next_repeat = now + REPEAT_INTERVAL;
+                    Some(d)
+                } else {
+                    None
+                }
+            }
+        }
+    }
+}
+
+pub fn any_connected() -> bool {
+    !connected_gamepads().is_empty()
+}
+
+fn connected_gamepads() -> Vec<Gamepad> {
+    let pads = match web_sys::window().map(|w| w.navigator().get_gamepads()) {
+        Some(Ok(pads)) => pads,
+        _ => return Vec::new(),
+    };
+
+    pads.iter()
+        .filter_map(|pad| pad.dyn_into::<Gamepad>().ok())
+        .filter(|pad| pad.connected())
+        .collect()
+}
+
+fn read_direction(pad: &Gamepad) -> Option<Direction> {
+    let buttons = pad.buttons();
+    let pressed = |index: u32| {
+        buttons.get(index).dyn_into::<GamepadButton>().map(|b| b.pressed()).unwrap_or(false)
+    };
+
+    if pressed(BUTTON_UP) {
+        return Some(Direction::Up);
+    }
+    if pressed(BUTTON_DOWN) {
+        return Some(Direction::Down);
+    }
+    if pressed(BUTTON_LEFT) {
+        return Some(Direction::Left);
+    }
+    if pressed(BUTTON_RIGHT) {
+        return Some(Direction::Right);
+    }
+
+    let axes = pad.axes();
+    let x = axes.get(0).as_f64().unwrap_or(0.0);
+    let y = axes.get(1).as_f64().unwrap_or(0.0);
+    if x.abs() < DEADZONE && y.abs() < DEADZONE {
+        return None;
+    }
+
+    if x.abs() > y.abs() {
+        Some(if x > 0.0 { Direction::Right } else { Direction::Left })
+    } else {
+        Some(if y > 0.0 { Direction::Down } else { Direction::Up })
+    }
+}
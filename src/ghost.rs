@@ -0,0 +1,101 @@
+use gloo::storage::{LocalStorage, Storage};
+use serde::{Deserialize, Serialize};
+use yew::{html, Html};
+
+use crate::board_view::BoardView;
+use crate::mode::GameMode;
+use crate::replay::{Playthrough, Replay};
+use crate::spawn::SpawnPolicy;
+use crate::storage::Namespace;
+use crate::theme::{DisplayMode, Palette};
+
+const GHOSTS_KEY: &str = "ghosts";
+// Replays run to tens of kilobytes, so only the most recent few seeds keep one.
+const GHOST_LIMIT: usize = 5;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Run {
+    pub score: u64,
+    pub replay: Replay,
+}
+
+// The best finished run for each seed I've played on purpose: a daily, or
+// one picked with 同一种子再来一局.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Runs {
+    runs: Vec<Run>,
+    #[serde(skip)]
+    namespace: Namespace,
+}
+
+impl Runs {
+    pub fn load(namespace: &Namespace) -> Self {
+        let runs: Self = LocalStorage::get(namespace.key(GHOSTS_KEY)).unwrap_or_default();
+        Self { namespace: namespace.clone(), ..runs }
+    }
+
+    fn save(&self) {
+        if let Err(e) = LocalStorage::set(self.namespace.key(GHOSTS_KEY), self) {
+            log::warn!("Failed to save ghosts: {:?}", e);
+        }
+    }
+
+    pub fn best(&self, mode: GameMode, spawn: SpawnPolicy, seed: u64) -> Option<&Run> {
+        self.runs.iter().find(|run| (run.replay.mode, run.replay.spawn, run.replay.seed) == (mode, spawn, seed))
+    }
+
+    pub fn record(&mut self, run: Run) {
+        let (mode, spawn, seed) = (run.replay.mode, run.replay.spawn, run.replay.seed);
+        if self.best(mode, spawn, seed).is_some_and(|best| best.score >= run.score) {
+            return;
+        }
+        self.runs.retain(|best| (best.replay.mode, best.replay.spawn, best.replay.seed) != (mode, spawn, seed));
+        self.runs.push(run);
+        if self.runs.len() > GHOST_LIMIT {
+            self.runs.drain(..self.runs.len() - GHOST_LIMIT);
+        }
+        self.save();
+    }
+}
+
+// The best run replayed alongside the live game, kept at the same move count.
+pub struct Ghost {
+    run: Run,
+    playthrough: Playthrough,
+}
+
+impl Ghost {
+    pub fn new(run: Run) -> Self {
+        let playthrough = Playthrough::new(&run.replay);
+        Self { run, playthrough }
+    }
+
+    // Walks forward from where it was, or from the start after an undo.
+    pub fn seek(&mut self, played: usize) {
+        if played < self.playthrough.played {
+            self.playthrough = Playthrough::new(&self.run.replay);
+        }
+        let target = played.min(self.run.replay.steps.len());
+        while self.playthrough.played < target {
+            let direction = self.run.replay.steps[self.playthrough.played].direction;
+            if self.playthrough.advance(direction).is_err() {
+                break;
+            }
+        }
+    }
+
+    pub fn view(&self, palette: Palette, display: DisplayMode) -> Html {
+        let finished = self.playthrough.played >= self.run.replay.steps.len();
+        html! {
+            <div class="ghost">
+            <p>{ format!("最好的一局（{}分）", self.run.score) }</p>
+            <BoardView board={self.playthrough.board} {palette} {display} />
+            <p>{ if finished {
+                format!("那局在第{}步结束", self.run.replay.steps.len())
+            } else {
+                format!("同一步时{}分", self.playthrough.score)
+            } }</p>
+            </div>
+        }
+    }
+}
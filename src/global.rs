@@ -0,0 +1,126 @@
+use gloo::storage::{LocalStorage, Storage};
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::{JsCast, JsValue};
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{Request, RequestInit, Response};
+use yew::{html, Html};
+
+use crate::board::Direction;
+use crate::mode::GameMode;
+use crate::storage::Namespace;
+
+pub const REPLAY_HASH_START: u32 = 0x811c9dc5;
+const OUTBOX_KEY: &str = "global-outbox";
+
+// What a finished game sends: enough for the server to replay the seed and
+// check the moves against the hash.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Submission {
+    pub name: String,
+    pub score: u64,
+    pub mode: GameMode,
+    pub seed: u64,
+    pub moves: u64,
+    pub replay_hash: String,
+    pub date: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct GlobalEntry {
+    pub name: String,
+    pub score: u64,
+    pub mode: GameMode,
+    pub moves: u64,
+    pub date: String,
+}
+
+// FNV-1a over the directions played, the same hash handoff codes use.
+pub fn replay_hash(hash: u32, direction: Direction) -> u32 {
+    let byte = match direction {
+        Direction::Up => b'U',
+        Direction::Down => b'D',
+        Direction::Left => b'L',
+        Direction::Right => b'R',
+    };
+    (hash ^ byte as u32).wrapping_mul(0x01000193)
+}
+
+pub fn format_hash(hash: u32) -> String {
+    format!("{:08x}", hash)
+}
+
+fn describe(error: JsValue) -> String {
+    error.as_string().unwrap_or_else(|| format!("{:?}", error))
+}
+
+async fn fetch(url: &str, body: Option<String>) -> Result<String, String> {
+    let mut init = RequestInit::new();
+    init.method(if body.is_some() { "POST" } else { "GET" });
+    if let Some(body) = &body {
+        init.body(Some(&JsValue::from_str(body)));
+    }
+    let request = Request::new_with_str_and_init(url, &init).map_err(describe)?;
+    if body.is_some() {
+        request.headers().set("Content-Type", "application/json").map_err(describe)?;
+    }
+    let window = web_sys::window().ok_or("没有window")?;
+    let response: Response = JsFuture::from(window.fetch_with_request(&request)).await
+        .map_err(describe)?
+        .dyn_into()
+        .map_err(describe)?;
+    if !response.ok() {
+        return Err(format!("HTTP {}", response.status()));
+    }
+    let text = JsFuture::from(response.text().map_err(describe)?).await.map_err(describe)?;
+    Ok(text.as_string().unwrap_or_default())
+}
+
+pub async fn submit(endpoint: String, submission: Submission) -> Result<(), String> {
+    let body = serde_json::to_string(&submission).map_err(|e| e.to_string())?;
+    fetch(&endpoint, Some(body)).await.map(|_| ())
+}
+
+fn queued(namespace: &Namespace) -> Vec<Submission> {
+    LocalStorage::get(namespace.key(OUTBOX_KEY)).unwrap_or_default()
+}
+
+// A game finished offline still goes up, the next time there's a network.
+pub fn queue(namespace: &Namespace, submission: Submission) {
+    let mut queued = queued(namespace);
+    queued.push(submission);
+    if let Err(e) = LocalStorage::set(namespace.key(OUTBOX_KEY), &queued) {
+        log::warn!("Failed to save queued submissions: {:?}", e);
+    }
+}
+
+// Taken out for sending; any that fail again are queued again.
+pub fn take_queued(namespace: &Namespace) -> Vec<Submission> {
+    let queued = queued(namespace);
+    if !queued.is_empty() {
+        LocalStorage::delete(namespace.key(OUTBOX_KEY));
+    }
+    queued
+}
+
+pub async fn top(endpoint: String) -> Result<Vec<GlobalEntry>, String> {
+    let text = fetch(&endpoint, None).await?;
+    serde_json::from_str(&text).map_err(|e| e.to_string())
+}
+
+pub fn view(entries: &[GlobalEntry]) -> Html {
+    html! {
+        <table class="stats">
+        <tr><th>{ "#" }</th><th>{ "名字" }</th><th>{ "模式" }</th><th>{ "分数" }</th><th>{ "步数" }</th><th>{ "日期" }</th></tr>
+        { entries.iter().enumerate().map(|(i, entry)| html! {
+            <tr>
+            <td>{ i + 1 }</td>
+            <td>{ &entry.name }</td>
+            <td>{ entry.mode.label() }</td>
+            <td>{ entry.score }</td>
+            <td>{ entry.moves }</td>
+            <td>{ &entry.date }</td>
+            </tr>
+        }).collect::<Html>() }
+        </table>
+    }
+}
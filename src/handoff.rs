@@ -0,0 +1,182 @@
+use serde::{Deserialize, Serialize};
+
+use crate::board::{Board, Cell, Position};
+use crate::mode::GameMode;
+use crate::powerups::PowerUps;
+use crate::rng::Rng;
+use crate::stats::Stats;
+
+const LIFETIME_MS: f64 = 30.0 * 60.0 * 1000.0;
+const WALL: i64 = -1;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Handoff {
+    pub mode: GameMode,
+    pub cells: Vec<i64>,
+    pub score: u64,
+    pub moves: u64,
+    pub won: bool,
+    pub is_dead: bool,
+    pub stats: Stats,
+    pub seed: u64,
+    pub rng: Rng,
+    pub powerups: PowerUps,
+    pub remaining: Option<u32>,
+    pub expires: f64,
+    // The day a daily belongs to, so finishing it tomorrow still counts for
+    // today. Both are missing from codes made before they were carried.
+    #[serde(default)]
+    pub daily_date: Option<String>,
+    #[serde(default)]
+    pub undos: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HandoffError {
+    Malformed,
+    Checksum,
+    Expired,
+}
+
+impl HandoffError {
+    pub fn message(&self) -> &'static str {
+        match self {
+            HandoffError::Malformed => "接力码看不懂",
+            HandoffError::Checksum => "接力码抄错了",
+            HandoffError::Expired => "接力码过期了",
+        }
+    }
+}
+
+impl Handoff {
+    pub fn cells(board: &Board) -> Vec<i64> {
+        board.positions().map(|p| match board[p] {
+            Cell::Empty => 0,
+            // Tiles top out at 2^63, which wraps to i64::MIN and back; never -1.
+            Cell::Value(v) => v as i64,
+            Cell::Wall => WALL,
+        }).collect()
+    }
+
+    pub fn expiry(now: f64) -> f64 {
+        now + LIFETIME_MS
+    }
+
+    pub fn board(&self) -> Board {
+        let mut board = self.mode.board();
+        let positions: Vec<Position> = board.positions().collect();
+        for (p, cell) in positions.into_iter().zip(&self.cells) {
+            let cell = match *cell {
+                0 => Cell::Empty,
+                WALL => Cell::Wall,
+                v => Cell::Value(v as u64),
+            };
+            board.set(p.position(), cell);
+        }
+        board
+    }
+
+    // The code is base64 JSON plus an FNV-1a checksum, so a mistyped character
+    // is caught before it can restore a broken game.
+    pub fn encode(&self) -> Option<String> {
+        let json = serde_json::to_string(self).ok()?;
+        let encoded = web_sys::window()?.btoa(&json).ok()?;
+        Some(format!("{}-{:08x}", encoded, checksum(&json)))
+    }
+
+    pub fn decode(code: &str, now: f64) -> Result<Self, HandoffError> {
+        let (encoded, sum) = code.trim().rsplit_once('-').ok_or(HandoffError::Malformed)?;
+        let sum = u32::from_str_radix(sum, 16).map_err(|_| HandoffError::Malformed)?;
+        let json = web_sys::window()
+            .and_then(|w| w.atob(encoded).ok())
+            .ok_or(HandoffError::Malformed)?;
+        Self::verify(&json, sum, now)
+    }
+
+    // Everything after the base64, which needs a browser.
+    fn verify(json: &str, sum: u32, now: f64) -> Result<Self, HandoffError> {
+        if checksum(json) != sum {
+            return Err(HandoffError::Checksum);
+        }
+        let handoff: Handoff = serde_json::from_str(json).map_err(|_| HandoffError::Malformed)?;
+        if handoff.cells.len() != handoff.mode.board().positions().count() {
+            return Err(HandoffError::Malformed);
+        }
+        if now > handoff.expires {
+            return Err(HandoffError::Expired);
+        }
+        Ok(handoff)
+    }
+}
+
+pub fn checksum(text: &str) -> u32 {
+    text.bytes().fold(0x811c9dc5u32, |hash, byte| (hash ^ byte as u32).wrapping_mul(0x01000193))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn handoff() -> Handoff {
+        let mut board = GameMode::Classic.board();
+        board.set(0, Cell::Value(8));
+        board.set(Position { row: 1, column: 1 }.position(), Cell::Wall);
+        Handoff {
+            mode: GameMode::Classic,
+            cells: Handoff::cells(&board),
+            score: 120,
+            moves: 14,
+            won: false,
+            is_dead: false,
+            stats: Stats::default(),
+            seed: 42,
+            rng: Rng::new(42),
+            powerups: PowerUps::default(),
+            remaining: None,
+            expires: 1000.0,
+            daily_date: Some("2024-05-01".to_string()),
+            undos: 2,
+        }
+    }
+
+    fn sealed(handoff: &Handoff) -> (String, u32) {
+        let json = serde_json::to_string(handoff).unwrap();
+        let sum = checksum(&json);
+        (json, sum)
+    }
+
+    #[test]
+    fn handoffs_round_trip() {
+        let original = handoff();
+        let (json, sum) = sealed(&original);
+        let decoded = Handoff::verify(&json, sum, 500.0).unwrap();
+        assert_eq!(serde_json::to_string(&decoded).unwrap(), json);
+        assert_eq!(decoded.board()[Position { row: 0, column: 0 }], Cell::Value(8));
+        assert_eq!(decoded.board()[Position { row: 1, column: 1 }], Cell::Wall);
+    }
+
+    #[test]
+    fn bad_codes_are_refused() {
+        let (json, sum) = sealed(&handoff());
+        assert_eq!(Handoff::verify(&json, sum ^ 1, 500.0).unwrap_err(), HandoffError::Checksum);
+        assert_eq!(Handoff::verify(&json, sum, 1500.0).unwrap_err(), HandoffError::Expired);
+        assert_eq!(Handoff::decode("no checksum", 500.0).unwrap_err(), HandoffError::Malformed);
+        assert_eq!(Handoff::decode("abc-xyz", 500.0).unwrap_err(), HandoffError::Malformed);
+
+        let short = Handoff { cells: vec![0; 3], ..handoff() };
+        let (json, sum) = sealed(&short);
+        assert_eq!(Handoff::verify(&json, sum, 500.0).unwrap_err(), HandoffError::Malformed);
+    }
+
+    #[test]
+    fn codes_from_before_dailies_and_undos_still_load() {
+        let (json, _) = sealed(&handoff());
+        let mut old: serde_json::Value = serde_json::from_str(&json).unwrap();
+        let fields = old.as_object_mut().unwrap();
+        fields.remove("daily_date");
+        fields.remove("undos");
+        let json = old.to_string();
+        let decoded = Handoff::verify(&json, checksum(&json), 500.0).unwrap();
+        assert_eq!((decoded.daily_date, decoded.undos), (None, 0));
+    }
+}
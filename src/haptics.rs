@@ -0,0 +1,42 @@
+use js_sys::Array;
+use wasm_bindgen::JsValue;
+use web_sys::Navigator;
+
+use crate::board::MoveResult;
+
+const MERGE_MS: u32 = 15;
+const MAX_MERGE_PULSES: usize = 3;
+const GAME_OVER_PATTERN: [u32; 5] = [80, 60, 80, 60, 240];
+
+// Desktop browsers expose vibrate() too but have nothing to shake.
+fn navigator() -> Option<Navigator> {
+    let navigator = web_sys::window()?.navigator();
+    (navigator.max_touch_points() > 0).then_some(navigator)
+}
+
+pub fn available() -> bool {
+    navigator().is_some()
+}
+
+fn vibrate(pattern: &[u32]) {
+    if let Some(navigator) = navigator() {
+        let pattern: Array = pattern.iter().map(|ms| JsValue::from(*ms)).collect();
+        navigator.vibrate_with_pattern(&pattern);
+    }
+}
+
+// One short pulse per merge, so a double merge feels different from a single.
+pub fn merges(result: &MoveResult) {
+    let count = result.merges.len();
+    if count == 0 {
+        return;
+    }
+    let pattern: Vec<u32> = (0..count.min(MAX_MERGE_PULSES) * 2 - 1)
+        .map(|i| if i % 2 == 0 { MERGE_MS } else { MERGE_MS * 2 })
+        .collect();
+    vibrate(&pattern);
+}
+
+pub fn game_over() {
+    vibrate(&GAME_OVER_PATTERN);
+}
@@ -0,0 +1,29 @@
+use yew::{html, Html};
+
+use crate::ai::{self, MoveEvaluation};
+use crate::settings::Action;
+
+pub fn view(evaluations: &[MoveEvaluation]) -> Html {
+    let best = ai::best(evaluations).map(|e| e.direction);
+    html! {
+        <div class="hint">
+        if let Some(direction) = best {
+            <p>{ format!("建议往{}走", Action::Move(direction).label()) }</p>
+        } else {
+            <p>{ "哪个方向都动不了" }</p>
+        }
+        <table class="stats">
+        <tr><th>{ "方向" }</th><th>{ "期望" }</th><th>{ "这步得分" }</th><th>{ "空格" }</th><th>{ "单调性（越低越好）" }</th></tr>
+        { evaluations.iter().map(|e| html! {
+            <tr class={if Some(e.direction) == best { "best" } else { "" }}>
+            <td>{ Action::Move(e.direction).label() }</td>
+            <td>{ format!("{:.1}", e.expected) }</td>
+            <td>{ e.points }</td>
+            <td>{ e.empties }</td>
+            <td>{ format!("{:.1}", e.monotonicity) }</td>
+            </tr>
+        }).collect::<Html>() }
+        </table>
+        </div>
+    }
+}
@@ -0,0 +1,227 @@
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::JsCast;
+use web_sys::{HtmlElement, KeyboardEvent};
+
+use crate::board::Direction;
+use crate::gamepad::GamepadPoller;
+use crate::settings::{Action, Settings};
+
+const SWIPE_DISTANCE: i32 = 30;
+const PRIORITY_WINDOW_MS: f64 = 300.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum Source {
+    Keyboard,
+    Touch,
+    Gamepad,
+}
+
+pub struct KeyPress {
+    pub key: String,
+    // Ctrl, or Cmd on a Mac.
+    pub command: bool,
+    pub shift: bool,
+    // Typed into a text field, whose own undo wins.
+    pub editing: bool,
+}
+
+pub enum RawInput {
+    KeyDown(KeyPress),
+    TouchStart(i32, i32),
+    TouchEnd(i32, i32),
+    GamepadFrame(f64),
+}
+
+pub trait InputSource {
+    fn source(&self) -> Source;
+    fn handle(&mut self, raw: &RawInput, settings: &Settings) -> Option<Action>;
+}
+
+pub struct Keyboard;
+
+#[derive(Default)]
+pub struct Touch {
+    start: Option<(i32, i32)>,
+}
+
+// Whether the board is what the player is looking at. The component sets
+// this from its open panels; a focused text field counts on its own.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Focus {
+    #[default]
+    Board,
+    Overlay,
+}
+
+pub struct InputPipeline {
+    sources: Vec<Box<dyn InputSource>>,
+    last: Option<(Source, f64)>,
+    focus: Focus,
+    // Off for race seats, which only take their bound moves: both listen on
+    // the window, so one Ctrl+Z would undo both boards.
+    shortcuts: bool,
+}
+
+impl Source {
+    pub const ALL: [Source; 3] = [Source::Keyboard, Source::Touch, Source::Gamepad];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Source::Keyboard => "键盘",
+            Source::Touch => "触屏滑动",
+            Source::Gamepad => "手柄",
+        }
+    }
+
+    fn priority(&self) -> u8 {
+        match self {
+            Source::Keyboard => 2,
+            Source::Touch => 1,
+            Source::Gamepad => 0,
+        }
+    }
+}
+
+impl KeyPress {
+    pub fn from_event(event: &KeyboardEvent) -> Self {
+        let editing = event.target()
+            .and_then(|target| target.dyn_into::<HtmlElement>().ok())
+            .is_some_and(|element| editable(&element));
+        Self {
+            key: event.key(),
+            command: event.ctrl_key() || event.meta_key(),
+            shift: event.shift_key(),
+            editing,
+        }
+    }
+
+    // The usual Ctrl/Cmd+Z and Ctrl/Cmd+Shift+Z, on top of whatever keys are bound.
+    fn shortcut(&self) -> Option<Action> {
+        if !self.command || self.editing || !self.key.eq_ignore_ascii_case("z") {
+            return None;
+        }
+        Some(if self.shift { Action::Redo } else { Action::Undo })
+    }
+}
+
+fn editable(element: &HtmlElement) -> bool {
+    element.is_content_editable() || matches!(element.tag_name().as_str(), "INPUT" | "TEXTAREA" | "SELECT")
+}
+
+pub(crate) fn field_focused() -> bool {
+    web_sys::window()
+        .and_then(|w| w.document())
+        .and_then(|d| d.active_element())
+        .and_then(|element| element.dyn_into::<HtmlElement>().ok())
+        .is_some_and(|element| editable(&element))
+}
+
+impl InputSource for Keyboard {
+    fn source(&self) -> Source {
+        Source::Keyboard
+    }
+
+    // Bound keys only count on their own, so Ctrl+R still reloads the page
+    // rather than restarting the game.
+    fn handle(&mut self, raw: &RawInput, settings: &Settings) -> Option<Action> {
+        match raw {
+            RawInput::KeyDown(press) if press.command => press.shortcut(),
+            RawInput::KeyDown(press) => settings.key_bindings.action(&press.key),
+            _ => None,
+        }
+    }
+}
+
+impl InputSource for Touch {
+    fn source(&self) -> Source {
+        Source::Touch
+    }
+
+    fn handle(&mut self, raw: &RawInput, _settings: &Settings) -> Option<Action> {
+        match *raw {
+            RawInput::TouchStart(x, y) => {
+                self.start = Some((x, y));
+                None
+            }
+            RawInput::TouchEnd(x, y) => {
+                let (dx, dy) = self.start.take().map(|(sx, sy)| (x - sx, y - sy))?;
+                if dx.abs().max(dy.abs()) < SWIPE_DISTANCE {
+                    return None;
+                }
+                let direction = if dx.abs() > dy.abs() {
+                    if dx > 0 { Direction::Right } else { Direction::Left }
+                } else if dy > 0 {
+                    Direction::Down
+                } else {
+                    Direction::Up
+                };
+                Some(Action::Move(direction))
+            }
+            _ => None,
+        }
+    }
+}
+
+impl InputSource for GamepadPoller {
+    fn source(&self) -> Source {
+        Source::Gamepad
+    }
+
+    fn handle(&mut self, raw: &RawInput, _settings: &Settings) -> Option<Action> {
+        match *raw {
+            RawInput::GamepadFrame(now) => self.poll(now).map(Action::Move),
+            _ => None,
+        }
+    }
+}
+
+impl Default for InputPipeline {
+    fn default() -> Self {
+        Self {
+            sources: vec![Box::new(Keyboard), Box::new(Touch::default()), Box::new(GamepadPoller::default())],
+            last: None,
+            focus: Focus::default(),
+            shortcuts: true,
+        }
+    }
+}
+
+impl InputPipeline {
+    pub fn set_focus(&mut self, focus: Focus) {
+        self.focus = focus;
+    }
+
+    pub fn set_shortcuts(&mut self, shortcuts: bool) {
+        self.shortcuts = shortcuts;
+    }
+
+    // Keys typed into a field never reach the game, and neither do shortcuts
+    // when they're switched off. Moves are also dropped
+    // while an overlay is up or any field has focus, so a stick or a swipe
+    // can't play the board behind a dialog.
+    fn guarded(&self, raw: &RawInput, action: Action) -> bool {
+        if matches!(raw, RawInput::KeyDown(press) if press.editing || (press.command && !self.shortcuts)) {
+            return true;
+        }
+        matches!(action, Action::Move(_)) && (self.focus == Focus::Overlay || field_focused())
+    }
+
+    // A lower-priority source is ignored for a moment after a higher-priority one moved,
+    // so a drifting stick can't fight the keyboard.
+    pub fn dispatch(&mut self, raw: &RawInput, settings: &Settings, now: f64) -> Option<(Source, Action)> {
+        let (source, action) = self.sources.iter_mut()
+            .filter(|s| settings.input_enabled(s.source()))
+            .find_map(|s| s.handle(raw, settings).map(|action| (s.source(), action)))?;
+        if self.guarded(raw, action) {
+            return None;
+        }
+
+        if let Some((previous, at)) = self.last {
+            if previous.priority() > source.priority() && now - at < PRIORITY_WINDOW_MS {
+                return None;
+            }
+        }
+        self.last = Some((source, now));
+        Some((source, action))
+    }
+}
@@ -0,0 +1,40 @@
+use web_sys::HtmlInputElement;
+use yew::{html, Callback, Event, Html, MouseEvent, TargetCast};
+
+use crate::board::{Board, Cell, Position};
+
+// Debug-only popover for one cell. Values are raw, never display-doubled.
+pub fn view(board: &Board, p: Position, onset: Callback<Cell>, onclose: Callback<MouseEvent>) -> Html {
+    let cell = board[p];
+    let meta = board.meta(p);
+    let raw = match cell {
+        Cell::Empty => "空".to_string(),
+        Cell::Value(v) => v.to_string(),
+        Cell::Wall => "墙".to_string(),
+    };
+    let onchange = onset.reform(|event: Event| {
+        let value = event.target_unchecked_into::<HtmlInputElement>().value();
+        match value.trim().parse::<u64>() {
+            Ok(0) | Err(_) => Cell::Empty,
+            Ok(v) => Cell::Value(v),
+        }
+    });
+    html! {
+        <div class="inspector" role="dialog" aria-label="格子详情">
+        <table class="stats">
+        <tr><td>{ "位置" }</td><td>{ format!("({}, {})，下标{}", p.row, p.column, p.position()) }</td></tr>
+        <tr><td>{ "原始值" }</td><td>{ raw }</td></tr>
+        <tr><td>{ "编号" }</td><td>{ if meta.id == 0 { "-".to_string() } else { format!("#{}", meta.id) } }</td></tr>
+        <tr><td>{ "停留" }</td><td>{ format!("{}步", meta.age) }</td></tr>
+        <tr><td>{ "合并次数" }</td><td>{ meta.merges }</td></tr>
+        <tr>
+        <td>{ "改成" }</td>
+        <td><input type="number" min="0" value={cell.value().to_string()} {onchange} /></td>
+        </tr>
+        </table>
+        <button onclick={onset.reform(|_| Cell::Wall)}>{ "放墙" }</button>
+        <button onclick={onset.reform(|_| Cell::Empty)}>{ "清空" }</button>
+        <button onclick={onclose}>{ "关闭" }</button>
+        </div>
+    }
+}
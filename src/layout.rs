@@ -0,0 +1,44 @@
+use wasm_bindgen::JsValue;
+
+const MAX_CELL: f64 = 100.0;
+const MIN_CELL: f64 = 28.0;
+// What the board may take of the window; the rest is score, buttons and panels.
+const WIDTH_SHARE: f64 = 0.94;
+const HEIGHT_SHARE: f64 = 0.6;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Viewport {
+    pub width: f64,
+    pub height: f64,
+}
+
+impl Viewport {
+    pub fn current() -> Self {
+        let size = |value: Result<JsValue, JsValue>| value.ok().and_then(|v| v.as_f64()).unwrap_or(0.0);
+        match web_sys::window() {
+            Some(window) => Self { width: size(window.inner_width()), height: size(window.inner_height()) },
+            None => Self { width: 0.0, height: 0.0 },
+        }
+    }
+
+    // The share of the window one of several side-by-side boards gets.
+    pub fn split(self, boards: u32) -> Self {
+        Self { width: self.width / boards as f64, ..self }
+    }
+
+    // The biggest square cell that fits the board in both directions, capped
+    // at the desktop size so a 4×4 board doesn't fill a whole monitor.
+    pub fn cell_size(&self, rows: u8, columns: u8) -> f64 {
+        if self.width <= 0.0 || self.height <= 0.0 {
+            return MAX_CELL;
+        }
+        let across = self.width * WIDTH_SHARE / columns as f64;
+        let down = self.height * HEIGHT_SHARE / rows as f64;
+        across.min(down).clamp(MIN_CELL, MAX_CELL).floor()
+    }
+
+    // The custom properties the stylesheet sizes the board from.
+    pub fn board_style(&self, rows: u8, columns: u8) -> String {
+        format!("--rows:{};--cols:{};--cell-size:{}px;", rows, columns, self.cell_size(rows, columns))
+    }
+}
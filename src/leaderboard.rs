@@ -0,0 +1,109 @@
+use gloo::storage::{LocalStorage, Storage};
+use serde::{Deserialize, Serialize};
+use yew::{html, Callback, Html, MouseEvent};
+
+use crate::storage::Namespace;
+
+const LEADERBOARD_KEY: &str = "leaderboard";
+const PLAYER_NAME_KEY: &str = "player-name";
+const CAPACITY: usize = 10;
+pub const NAME_LENGTH: usize = 12;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Entry {
+    pub name: String,
+    pub score: u64,
+    pub highest_tile: u64,
+    pub moves: u64,
+    pub date: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Leaderboard {
+    entries: Vec<Entry>,
+    #[serde(skip)]
+    namespace: Namespace,
+    #[serde(skip)]
+    bucket: Option<String>,
+}
+
+impl Leaderboard {
+    pub fn load(namespace: &Namespace) -> Self {
+        Self::load_bucket(namespace, None)
+    }
+
+    // Buckets are separate boards stored beside the main one, e.g. for an event.
+    pub fn load_bucket(namespace: &Namespace, bucket: Option<&str>) -> Self {
+        let bucket = bucket.map(str::to_string);
+        let key = Self::key_for(namespace, bucket.as_deref());
+        let leaderboard: Self = LocalStorage::get(key).unwrap_or_default();
+        Self { namespace: namespace.clone(), bucket, ..leaderboard }
+    }
+
+    fn key_for(namespace: &Namespace, bucket: Option<&str>) -> String {
+        match bucket {
+            Some(bucket) => namespace.key(&format!("{}.{}", LEADERBOARD_KEY, bucket)),
+            None => namespace.key(LEADERBOARD_KEY),
+        }
+    }
+
+    fn key(&self) -> String {
+        Self::key_for(&self.namespace, self.bucket.as_deref())
+    }
+
+    fn save(&self) {
+        if let Err(e) = LocalStorage::set(self.key(), self) {
+            log::warn!("Failed to save leaderboard: {:?}", e);
+        }
+    }
+
+    pub fn record(&mut self, entry: Entry) {
+        self.entries.push(entry);
+        self.entries.sort_by_key(|entry| std::cmp::Reverse(entry.score));
+        self.entries.truncate(CAPACITY);
+        self.save();
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        LocalStorage::delete(self.key());
+    }
+
+    pub fn view(&self, onclear: Callback<MouseEvent>) -> Html {
+        html! {
+            <div class="leaderboard">
+            <table class="stats">
+            <tr><th>{ "#" }</th><th>{ "名字" }</th><th>{ "分数" }</th><th>{ "最大方块" }</th><th>{ "步数" }</th><th>{ "日期" }</th></tr>
+            { self.entries.iter().enumerate().map(|(i, entry)| {
+                html! {
+                    <tr>
+                    <td>{ i + 1 }</td>
+                    <td>{ &entry.name }</td>
+                    <td>{ entry.score }</td>
+                    <td>{ entry.highest_tile }</td>
+                    <td>{ entry.moves }</td>
+                    <td>{ &entry.date }</td>
+                    </tr>
+                }
+            }).collect::<Html>() }
+            </table>
+            <button onclick={onclear}>{ "清空" }</button>
+            </div>
+        }
+    }
+}
+
+pub fn player_name(namespace: &Namespace) -> String {
+    LocalStorage::get(namespace.key(PLAYER_NAME_KEY)).unwrap_or_else(|_| "无名氏".to_string())
+}
+
+pub fn set_player_name(namespace: &Namespace, name: &str) {
+    if let Err(e) = LocalStorage::set(namespace.key(PLAYER_NAME_KEY), name) {
+        log::warn!("Failed to save player name: {:?}", e);
+    }
+}
+
+pub fn today() -> String {
+    let iso: String = js_sys::Date::new_0().to_iso_string().into();
+    iso.chars().take(10).collect()
+}
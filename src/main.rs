@@ -1,6 +1,114 @@
+mod achievements;
+mod analysis;
+mod audio;
+mod autosave;
+mod backup;
+mod bench;
+mod board_view;
+#[cfg(not(feature = "offline"))]
+mod broadcast;
+mod changelog;
+mod confirm;
+mod daily;
+mod debugger;
+mod editor;
+mod flags;
+mod game_loop;
 mod game_view_2048;
+mod gamepad;
+mod ghost;
+mod global;
+mod handoff;
+mod haptics;
+mod hint;
+mod input;
+mod inspector;
+mod layout;
+mod leaderboard;
+mod mascot;
+mod mode;
+mod narration;
+#[cfg(not(feature = "offline"))]
+mod net;
+#[cfg(not(feature = "offline"))]
+mod online;
+mod perf;
+mod powerups;
+mod preview;
+mod puzzles;
+mod pwa;
+mod race;
+mod records;
+mod replay;
+mod seasons;
+mod settings;
+mod sparkline;
+mod spawn;
+mod stats;
+mod storage;
+mod summary;
+mod theme;
+mod tile_view;
+mod totals;
+mod tournament;
+mod tutorial;
+
+use wasm_bindgen::JsCast;
+use web_sys::Element;
+use engine_2048::{ai, board, rng, rules};
+
+use game_view_2048::{GameProps, GameState};
+#[cfg(not(feature = "offline"))]
+use broadcast::{Broadcast, Spectate};
+#[cfg(not(feature = "offline"))]
+use online::Online;
+use race::Race;
+
+// <div data-yew-2048="left"></div> mounts a separate board with its own saves;
+// without any such element the game takes over the body as before.
+fn mount_points() -> Vec<Element> {
+    let nodes = web_sys::window()
+        .and_then(|w| w.document())
+        .and_then(|d| d.query_selector_all("[data-yew-2048]").ok());
+    match nodes {
+        Some(nodes) => (0..nodes.length())
+            .filter_map(|i| nodes.get(i)?.dyn_into::<Element>().ok())
+            .collect(),
+        None => Vec::new(),
+    }
+}
 
 fn main() {
     wasm_logger::init(wasm_logger::Config::default());
-    yew::start_app::<game_view_2048::GameState>();
+    pwa::register();
+    // ?race puts two boards side by side for two players on one keyboard.
+    if flags::query_param("race").is_some() {
+        yew::start_app::<Race>();
+        return;
+    }
+    // ?online opens the lobby for a game against someone on another machine;
+    // ?broadcast streams my game to whoever opens its ?spectate= link.
+    #[cfg(not(feature = "offline"))]
+    {
+        if flags::query_param("online").is_some() {
+            yew::start_app::<Online>();
+            return;
+        }
+        if flags::query_param("broadcast").is_some() {
+            yew::start_app::<Broadcast>();
+            return;
+        }
+        if flags::query_param("spectate").is_some() {
+            yew::start_app::<Spectate>();
+            return;
+        }
+    }
+    let mounts = mount_points();
+    if mounts.is_empty() {
+        yew::start_app::<GameState>();
+    }
+    for element in mounts {
+        let instance = element.get_attribute("data-yew-2048").unwrap_or_default();
+        yew::start_app_with_props_in_element::<GameState>(element, GameProps { instance, ..GameProps::default() });
+    }
 }
\ No newline at end of file
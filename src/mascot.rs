@@ -0,0 +1,92 @@
+use yew::{html, Html};
+
+use crate::board::MoveResult;
+
+const THRILLING_MERGE: u64 = 64;
+const NEAR_DEATH_EMPTIES: usize = 2;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Mood {
+    #[default]
+    Idle,
+    Happy,
+    Thrilled,
+    Worried,
+    Facepalm,
+    Celebrating,
+    Sad,
+}
+
+pub struct Reaction<'a> {
+    pub turn: &'a MoveResult,
+    pub blunder: bool,
+    pub empties: usize,
+    pub won: bool,
+    pub dead: bool,
+}
+
+impl Mood {
+    fn face(&self) -> &'static str {
+        match self {
+            Mood::Idle => "(・ω・)",
+            Mood::Happy => "(＾▽＾)",
+            Mood::Thrilled => "ヽ(°〇°)ﾉ",
+            Mood::Worried => "(；´Д｀)",
+            Mood::Facepalm => "(－‸ლ)",
+            Mood::Celebrating => "＼(^o^)／",
+            Mood::Sad => "(╥﹏╥)",
+        }
+    }
+
+    fn line(&self) -> &'static str {
+        match self {
+            Mood::Idle => "看你的了",
+            Mood::Happy => "合上了！",
+            Mood::Thrilled => "哇，大的来了！",
+            Mood::Worried => "快满了，小心点……",
+            Mood::Facepalm => "这步有点亏啊",
+            Mood::Celebrating => "赢啦！",
+            Mood::Sad => "没路走了",
+        }
+    }
+
+    fn class(&self) -> &'static str {
+        match self {
+            Mood::Idle => "mascot-idle",
+            Mood::Happy | Mood::Thrilled | Mood::Celebrating => "mascot-bounce",
+            Mood::Worried | Mood::Facepalm => "mascot-shake",
+            Mood::Sad => "mascot-droop",
+        }
+    }
+}
+
+// The end of the game outranks a blunder, which outranks anything the board did.
+pub fn react(reaction: Reaction) -> Mood {
+    let biggest = reaction.turn.merges.iter().map(|merge| merge.value).max().unwrap_or(0);
+    if reaction.won {
+        Mood::Celebrating
+    } else if reaction.dead {
+        Mood::Sad
+    } else if reaction.blunder {
+        Mood::Facepalm
+    } else if reaction.empties <= NEAR_DEATH_EMPTIES {
+        Mood::Worried
+    } else if biggest >= THRILLING_MERGE {
+        Mood::Thrilled
+    } else if biggest > 0 {
+        Mood::Happy
+    } else {
+        Mood::Idle
+    }
+}
+
+// `turn` keys the face so its animation replays even when the mood repeats.
+pub fn view(mood: Mood, turn: u64, animate: bool) -> Html {
+    let class = if animate { format!("mascot-face {}", mood.class()) } else { "mascot-face".to_string() };
+    html! {
+        <div class="mascot" aria-hidden="true">
+        <span key={turn.to_string()} {class}>{ mood.face() }</span>
+        <span class="mascot-line">{ mood.line() }</span>
+        </div>
+    }
+}
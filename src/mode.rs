@@ -0,0 +1,149 @@
+use serde::{Deserialize, Serialize};
+use yew::{html, Callback, Html};
+
+use crate::board::{Board, Cell, Event, Position};
+use crate::rng::Rng;
+use crate::rules::{Classic, Fibonacci, Rules, Threes};
+use crate::seasons::Season;
+
+// Mega milestones: a toast at every fourth doubling from a shown 4096 (raw 2^11).
+const FIRST_MILESTONE: u32 = 11;
+const MILESTONE_STEP: usize = 4;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum GameMode {
+    #[default]
+    Classic,
+    Zen,
+    TimeAttack,
+    Walls,
+    Fibonacci,
+    Threes,
+    Mega,
+    Daily,
+    Event,
+}
+
+impl GameMode {
+    pub const ALL: [GameMode; 9] = [
+        GameMode::Classic,
+        GameMode::Zen,
+        GameMode::TimeAttack,
+        GameMode::Walls,
+        GameMode::Fibonacci,
+        GameMode::Threes,
+        GameMode::Mega,
+        GameMode::Daily,
+        GameMode::Event,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            GameMode::Classic => "经典",
+            GameMode::Zen => "禅",
+            GameMode::TimeAttack => "限时",
+            GameMode::Walls => "障碍",
+            GameMode::Fibonacci => "斐波那契",
+            GameMode::Threes => "三的倍数",
+            GameMode::Mega => "巨型8×8",
+            GameMode::Daily => "每日挑战",
+            GameMode::Event => "限时活动",
+        }
+    }
+
+    pub fn endless(&self) -> bool {
+        matches!(self, GameMode::Zen)
+    }
+
+    pub fn stops_on_win(&self) -> bool {
+        matches!(self, GameMode::Classic | GameMode::Walls | GameMode::Fibonacci | GameMode::Threes | GameMode::Daily | GameMode::Event)
+    }
+
+    pub fn board(&self) -> Board {
+        match self {
+            GameMode::Mega => Board::new(8, 8),
+            _ => Board::default(),
+        }
+    }
+
+    // Mega never stops on a tile; it marks milestones instead. Returns the
+    // biggest one the highest tile passed going from `before` to `after`.
+    pub fn milestone(&self, before: u64, after: u64) -> Option<u64> {
+        if *self != GameMode::Mega {
+            return None;
+        }
+        (FIRST_MILESTONE..64).step_by(MILESTONE_STEP)
+            .map(|exponent| 1u64 << exponent)
+            .rfind(|milestone| before < *milestone && *milestone <= after)
+    }
+
+    // Classic rules on a seed shared by everyone for the UTC day.
+    pub fn daily(&self) -> bool {
+        matches!(self, GameMode::Daily)
+    }
+
+    pub fn doubles(&self) -> bool {
+        !matches!(self, GameMode::Fibonacci | GameMode::Threes)
+    }
+
+    pub fn rules(&self) -> &'static dyn Rules {
+        match self {
+            GameMode::Fibonacci => &Fibonacci,
+            GameMode::Threes => &Threes,
+            _ => &Classic,
+        }
+    }
+
+    pub fn time_limit(&self) -> Option<u32> {
+        match self {
+            GameMode::TimeAttack => Some(180),
+            _ => None,
+        }
+    }
+
+    pub fn walls(&self) -> Option<usize> {
+        match self {
+            GameMode::Walls => Some(3),
+            _ => None,
+        }
+    }
+
+    // Walls go down before the first tile, drawn from the game's own rng.
+    pub fn place_walls(&self, board: &mut Board, rng: &mut Rng) {
+        for _ in 0..self.walls().unwrap_or(0) {
+            let empties = board.empties();
+            let number = rng.below(empties.len());
+            board.set(empties[number], Cell::Wall);
+        }
+    }
+}
+
+// Endless mode's way out of a full board: the row with the least on it goes.
+pub fn endless_clears(board: &Board) -> Vec<Event> {
+    let weakest = (0..board.rows())
+        .min_by_key(|&row| (0..board.columns()).map(|column| board[Position{row, column}].value()).sum::<u64>())
+        .unwrap_or(0);
+    (0..board.columns())
+        .map(|column| Position{row: weakest, column})
+        .filter(|&p| matches!(board[p], Cell::Value(_)))
+        .map(|position| Event::Clear { position })
+        .collect()
+}
+
+// The event mode only shows while a season is running, under that season's name.
+pub fn view(current: GameMode, season: Option<&Season>, onselect: Callback<GameMode>) -> Html {
+    html! {
+        <div class="modes">
+        { GameMode::ALL.iter().filter(|mode| **mode != GameMode::Event || season.is_some()).map(|mode| {
+            let mode = *mode;
+            let label = match (mode, season) {
+                (GameMode::Event, Some(season)) => season.name.as_str(),
+                _ => mode.label(),
+            };
+            html! {
+                <button disabled={mode == current} onclick={onselect.reform(move |_| mode)}>{ label }</button>
+            }
+        }).collect::<Html>() }
+        </div>
+    }
+}
@@ -0,0 +1,294 @@
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::JsCast;
+use web_sys::{SpeechSynthesis, SpeechSynthesisUtterance, SpeechSynthesisVoice};
+
+use crate::board::{Board, Cell, Position};
+use crate::theme::DisplayMode;
+
+const EN_ONES: [&str; 20] = [
+    "zero", "one", "two", "three", "four", "five", "six", "seven", "eight", "nine", "ten",
+    "eleven", "twelve", "thirteen", "fourteen", "fifteen", "sixteen", "seventeen", "eighteen", "nineteen",
+];
+const EN_TENS: [&str; 10] = ["", "", "twenty", "thirty", "forty", "fifty", "sixty", "seventy", "eighty", "ninety"];
+const EN_SCALES: [&str; 7] = ["", "thousand", "million", "billion", "trillion", "quadrillion", "quintillion"];
+
+const ZH_DIGITS: [char; 10] = ['零', '一', '二', '三', '四', '五', '六', '七', '八', '九'];
+const ZH_UNITS: [&str; 4] = ["", "十", "百", "千"];
+const ZH_GROUPS: [&str; 5] = ["", "万", "亿", "兆", "京"];
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Locale {
+    #[default]
+    Chinese,
+    English,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MoveSummary {
+    pub moved: bool,
+    pub merged: Vec<u128>,
+    pub spawned: Option<u128>,
+    pub score: u64,
+    pub over: bool,
+}
+
+impl Locale {
+    pub const ALL: [Locale; 2] = [Locale::Chinese, Locale::English];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Locale::Chinese => "中文",
+            Locale::English => "English",
+        }
+    }
+
+    fn lang(&self) -> &'static str {
+        match self {
+            Locale::Chinese => "zh-CN",
+            Locale::English => "en-US",
+        }
+    }
+
+    pub fn number(&self, n: u128) -> String {
+        match self {
+            Locale::Chinese => chinese_number(n),
+            Locale::English => english_number(n),
+        }
+    }
+}
+
+impl MoveSummary {
+    // For the live region: digits, since screen readers voice those themselves.
+    pub fn written(&self) -> String {
+        if !self.moved {
+            return "动不了".to_string();
+        }
+        let mut parts = Vec::new();
+        if !self.merged.is_empty() {
+            let values: Vec<String> = self.merged.iter().map(u128::to_string).collect();
+            parts.push(format!("合成了{}", values.join("、")));
+        }
+        if let Some(value) = self.spawned {
+            parts.push(format!("新出一个{}", value));
+        }
+        parts.push(format!("分数{}", self.score));
+        parts.join("，")
+    }
+
+    pub fn spoken(&self, locale: Locale) -> String {
+        let words = |values: &[u128], separator: &str| {
+            values.iter().map(|v| locale.number(*v)).collect::<Vec<_>>().join(separator)
+        };
+        let mut parts = Vec::new();
+        match locale {
+            Locale::Chinese => {
+                if !self.moved {
+                    parts.push("动不了".to_string());
+                } else if !self.merged.is_empty() {
+                    parts.push(format!("合成了{}", words(&self.merged, "、")));
+                }
+                if let Some(value) = self.spawned {
+                    parts.push(format!("新出一个{}", locale.number(value)));
+                }
+                parts.push(format!("分数{}", locale.number(self.score as u128)));
+                if self.over {
+                    parts.push("游戏结束".to_string());
+                }
+                parts.join("，")
+            }
+            Locale::English => {
+                if !self.moved {
+                    parts.push("no move".to_string());
+                } else if !self.merged.is_empty() {
+                    parts.push(format!("merged {}", words(&self.merged, ", ")));
+                }
+                if let Some(value) = self.spawned {
+                    parts.push(format!("spawned a {}", locale.number(value)));
+                }
+                parts.push(format!("score {}", locale.number(self.score as u128)));
+                if self.over {
+                    parts.push("game over".to_string());
+                }
+                parts.join(", ")
+            }
+        }
+    }
+}
+
+// A row as the player would describe it: runs of empty squares count as one.
+enum Square {
+    Tile(u128),
+    Empty(usize),
+    Wall,
+}
+
+fn squares(board: &Board, row: u8, display: DisplayMode) -> Vec<Square> {
+    let mut squares = Vec::new();
+    for column in 0..board.columns() {
+        match (board[Position { row, column }], squares.last_mut()) {
+            (Cell::Empty, Some(Square::Empty(run))) => *run += 1,
+            (Cell::Empty, _) => squares.push(Square::Empty(1)),
+            (Cell::Value(v), _) => squares.push(Square::Tile(display.value(v))),
+            (Cell::Wall, _) => squares.push(Square::Wall),
+        }
+    }
+    squares
+}
+
+// The whole board row by row, for the live region.
+pub fn board_written(board: &Board, display: DisplayMode) -> String {
+    (0..board.rows()).map(|row| {
+        let words: Vec<String> = squares(board, row, display).iter().map(|square| match square {
+            Square::Tile(v) => v.to_string(),
+            Square::Empty(1) => "空".to_string(),
+            Square::Empty(run) => format!("空{}格", run),
+            Square::Wall => "墙".to_string(),
+        }).collect();
+        format!("第{}行：{}", row + 1, words.join("、"))
+    }).collect::<Vec<_>>().join("。")
+}
+
+pub fn board_spoken(board: &Board, display: DisplayMode, locale: Locale) -> String {
+    let rows = (0..board.rows()).map(|row| {
+        let words: Vec<String> = squares(board, row, display).iter().map(|square| match (locale, square) {
+            (_, Square::Tile(v)) => locale.number(*v),
+            (Locale::Chinese, Square::Empty(1)) => "空".to_string(),
+            (Locale::Chinese, Square::Empty(run)) => format!("空{}格", locale.number(*run as u128)),
+            (Locale::Chinese, Square::Wall) => "墙".to_string(),
+            (Locale::English, Square::Empty(1)) => "empty".to_string(),
+            (Locale::English, Square::Empty(run)) => format!("{} empty", locale.number(*run as u128)),
+            (Locale::English, Square::Wall) => "wall".to_string(),
+        }).collect();
+        match locale {
+            Locale::Chinese => format!("第{}行，{}", locale.number(row as u128 + 1), words.join("，")),
+            Locale::English => format!("row {}: {}", locale.number(row as u128 + 1), words.join(", ")),
+        }
+    });
+    rows.collect::<Vec<_>>().join(match locale { Locale::Chinese => "。", Locale::English => ". " })
+}
+
+fn english_below_thousand(n: u128) -> String {
+    let (hundreds, rest) = (n / 100, n % 100);
+    let rest = match rest {
+        0 => String::new(),
+        r if r < 20 => EN_ONES[r as usize].to_string(),
+        r if r % 10 == 0 => EN_TENS[(r / 10) as usize].to_string(),
+        r => format!("{}-{}", EN_TENS[(r / 10) as usize], EN_ONES[(r % 10) as usize]),
+    };
+    match (hundreds, rest.is_empty()) {
+        (0, _) => rest,
+        (h, true) => format!("{} hundred", EN_ONES[h as usize]),
+        (h, false) => format!("{} hundred {}", EN_ONES[h as usize], rest),
+    }
+}
+
+// 2048 reads "two thousand forty-eight".
+fn english_number(mut n: u128) -> String {
+    if n == 0 {
+        return EN_ONES[0].to_string();
+    }
+    let mut groups = Vec::new();
+    let mut scale = 0;
+    while n > 0 {
+        let group = n % 1000;
+        if group > 0 {
+            let words = english_below_thousand(group);
+            groups.push(if scale == 0 { words } else { format!("{} {}", words, EN_SCALES[scale]) });
+        }
+        n /= 1000;
+        scale += 1;
+    }
+    groups.reverse();
+    groups.join(" ")
+}
+
+fn chinese_group(n: u128) -> String {
+    let mut out = String::new();
+    let mut zero = false;
+    for position in (0..4).rev() {
+        let digit = (n / 10u128.pow(position as u32) % 10) as usize;
+        if digit == 0 {
+            zero = !out.is_empty();
+            continue;
+        }
+        if zero {
+            out.push(ZH_DIGITS[0]);
+            zero = false;
+        }
+        out.push(ZH_DIGITS[digit]);
+        out.push_str(ZH_UNITS[position]);
+    }
+    out
+}
+
+// 2048 reads "二千零四十八"; 12 reads "十二" rather than "一十二".
+fn chinese_number(n: u128) -> String {
+    if n == 0 {
+        return ZH_DIGITS[0].to_string();
+    }
+    let mut groups = Vec::new();
+    let mut rest = n;
+    while rest > 0 {
+        groups.push(rest % 10000);
+        rest /= 10000;
+    }
+
+    let mut out = String::new();
+    let mut zero = false;
+    for (i, group) in groups.iter().enumerate().rev() {
+        if *group == 0 {
+            zero = !out.is_empty();
+            continue;
+        }
+        if !out.is_empty() && (zero || *group < 1000) {
+            out.push(ZH_DIGITS[0]);
+        }
+        zero = false;
+        out.push_str(&chinese_group(*group));
+        out.push_str(ZH_GROUPS[i]);
+    }
+    match out.strip_prefix("一十") {
+        Some(rest) => format!("十{}", rest),
+        None => out,
+    }
+}
+
+fn synthesis() -> Option<SpeechSynthesis> {
+    web_sys::window()?.speech_synthesis().ok()
+}
+
+fn locale_voices(locale: Locale) -> Vec<SpeechSynthesisVoice> {
+    let prefix = &locale.lang()[..2];
+    match synthesis() {
+        Some(synthesis) => synthesis.get_voices().iter()
+            .filter_map(|voice| voice.dyn_into::<SpeechSynthesisVoice>().ok())
+            .filter(|voice| voice.lang().starts_with(prefix))
+            .collect(),
+        None => Vec::new(),
+    }
+}
+
+pub fn voices(locale: Locale) -> Vec<String> {
+    locale_voices(locale).iter().map(|voice| voice.name()).collect()
+}
+
+pub fn speak(text: &str, locale: Locale, voice: Option<&str>) {
+    let synthesis = match synthesis() {
+        Some(synthesis) => synthesis,
+        None => return,
+    };
+    let utterance = match SpeechSynthesisUtterance::new_with_text(text) {
+        Ok(utterance) => utterance,
+        Err(e) => {
+            log::warn!("Failed to create utterance: {:?}", e);
+            return;
+        }
+    };
+    utterance.set_lang(locale.lang());
+    if let Some(name) = voice {
+        let chosen = locale_voices(locale).into_iter().find(|v| v.name() == name);
+        utterance.set_voice(chosen.as_ref());
+    }
+    synthesis.cancel();
+    synthesis.speak(&utterance);
+}
@@ -0,0 +1,198 @@
+use gloo::events::EventListener;
+use gloo::timers::callback::Timeout;
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::JsCast;
+use web_sys::{MessageEvent, WebSocket};
+use yew::Callback;
+
+use crate::board::{Board, MoveResult};
+use crate::flags;
+use crate::rng::Rng;
+
+const CODE_ALPHABET: &[u8] = b"ABCDEFGHJKMNPQRSTUVWXYZ23456789";
+pub const CODE_LENGTH: usize = 6;
+const RETRY_BASE_MS: u32 = 1000;
+const RETRY_MAX_MS: u32 = 30_000;
+
+// Where a player stands after their latest move.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct State {
+    pub board: Board,
+    pub score: u64,
+    pub over: bool,
+}
+
+// The relay passes every frame on to the other players in the room. A Hello
+// (on every connect, including reconnects) is answered with a Sync, so
+// whoever dropped out catches up on the seed and the other board.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum Wire {
+    Hello { seed: Option<u64>, state: Option<State> },
+    Sync { seed: Option<u64>, state: Option<State> },
+    State(State),
+    // A broadcast move, numbered so spectators can drop repeats.
+    Frame { number: u64, result: MoveResult, state: State },
+}
+
+pub enum NetEvent {
+    Open,
+    Message(Box<Wire>),
+    Closed,
+    Retry,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Status {
+    Connecting,
+    Connected,
+    Reconnecting(u32),
+}
+
+impl Status {
+    pub fn text(&self) -> String {
+        match self {
+            Status::Connecting => "连接中…".to_string(),
+            Status::Connected => "已连接".to_string(),
+            Status::Reconnecting(attempt) => format!("断线了，第{}次重连…", attempt),
+        }
+    }
+}
+
+// A room that keeps itself connected: components pass every NetEvent through
+// `handle` first, and a dropped socket is retried with doubling waits.
+pub struct Relay {
+    room: String,
+    on: Callback<NetEvent>,
+    connection: Option<Connection>,
+    retry: Option<Timeout>,
+    pub status: Status,
+}
+
+impl Relay {
+    pub fn join(room: String, on: Callback<NetEvent>) -> Self {
+        let mut relay = Self { room, on, connection: None, retry: None, status: Status::Connecting };
+        relay.connect();
+        relay
+    }
+
+    pub fn room(&self) -> &str {
+        &self.room
+    }
+
+    pub fn send(&self, wire: &Wire) {
+        if let Some(connection) = &self.connection {
+            connection.send(wire);
+        }
+    }
+
+    pub fn handle(&mut self, event: &NetEvent) {
+        match event {
+            NetEvent::Open => self.status = Status::Connected,
+            NetEvent::Closed => {
+                self.connection = None;
+                self.reconnect_later();
+            }
+            NetEvent::Retry => {
+                self.retry = None;
+                if self.connection.is_none() {
+                    self.connect();
+                }
+            }
+            NetEvent::Message(_) => {}
+        }
+    }
+
+    fn connect(&mut self) {
+        self.connection = Connection::open(&self.room, self.on.clone());
+        if self.connection.is_none() {
+            self.reconnect_later();
+        }
+    }
+
+    fn reconnect_later(&mut self) {
+        let attempt = match self.status {
+            Status::Reconnecting(attempt) => attempt + 1,
+            _ => 1,
+        };
+        self.status = Status::Reconnecting(attempt);
+        let on = self.on.clone();
+        self.retry = Some(Timeout::new(retry_delay(attempt), move || on.emit(NetEvent::Retry)));
+    }
+}
+
+pub struct Connection {
+    socket: WebSocket,
+    _listeners: [EventListener; 3],
+}
+
+impl Connection {
+    pub fn open(room: &str, on: Callback<NetEvent>) -> Option<Self> {
+        let socket = match WebSocket::new(&format!("{}?room={}", relay_url()?, room)) {
+            Ok(socket) => socket,
+            Err(e) => {
+                log::warn!("Failed to open relay socket: {:?}", e);
+                return None;
+            }
+        };
+        let opened = on.reform(|_| NetEvent::Open);
+        let closed = on.reform(|_| NetEvent::Closed);
+        let listeners = [
+            EventListener::new(&socket, "open", move |_| opened.emit(())),
+            EventListener::new(&socket, "message", move |event| {
+                let text = event.dyn_ref::<MessageEvent>().and_then(|e| e.data().as_string());
+                match text.map(|text| serde_json::from_str::<Wire>(&text)) {
+                    Some(Ok(wire)) => on.emit(NetEvent::Message(Box::new(wire))),
+                    Some(Err(e)) => log::warn!("Ignoring relay message: {:?}", e),
+                    None => {}
+                }
+            }),
+            // An error is always followed by close, so close alone drives reconnects.
+            EventListener::new(&socket, "close", move |_| closed.emit(())),
+        ];
+        Some(Self { socket, _listeners: listeners })
+    }
+
+    pub fn send(&self, wire: &Wire) {
+        if self.socket.ready_state() != WebSocket::OPEN {
+            return;
+        }
+        let sent = serde_json::to_string(wire)
+            .map_err(|e| format!("{:?}", e))
+            .and_then(|text| self.socket.send_with_str(&text).map_err(|e| format!("{:?}", e)));
+        if let Err(e) = sent {
+            log::warn!("Failed to send to relay: {}", e);
+        }
+    }
+}
+
+impl Drop for Connection {
+    fn drop(&mut self) {
+        let _ = self.socket.close();
+    }
+}
+
+// ?relay=wss://… picks a relay; otherwise the page's own host serves one at /relay.
+fn relay_url() -> Option<String> {
+    if let Some(relay) = flags::query_param("relay") {
+        return Some(relay);
+    }
+    let location = web_sys::window()?.location();
+    let scheme = if location.protocol().ok()? == "https:" { "wss" } else { "ws" };
+    Some(format!("{}://{}/relay", scheme, location.host().ok()?))
+}
+
+// Letters and digits that can't be misread for each other when read out.
+pub fn join_code() -> String {
+    let mut rng = Rng::new(Rng::random_seed());
+    (0..CODE_LENGTH).map(|_| CODE_ALPHABET[rng.below(CODE_ALPHABET.len())] as char).collect()
+}
+
+pub fn normalize_code(typed: &str) -> Option<String> {
+    let code = typed.trim().to_uppercase();
+    (code.len() == CODE_LENGTH && code.bytes().all(|b| CODE_ALPHABET.contains(&b))).then_some(code)
+}
+
+// So a relay that is down isn't hammered.
+fn retry_delay(attempt: u32) -> u32 {
+    RETRY_BASE_MS.saturating_mul(1 << attempt.min(5)).min(RETRY_MAX_MS)
+}
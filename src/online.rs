@@ -0,0 +1,177 @@
+use web_sys::HtmlInputElement;
+use yew::{html, Component, Context, Html, TargetCast};
+
+use crate::board_view::BoardView;
+use crate::game_view_2048::{GameProps, GameState};
+use crate::net::{self, NetEvent, Relay, State, Status, Wire};
+use crate::race::Report;
+use crate::rng::Rng;
+use crate::theme::{DisplayMode, Palette};
+
+pub enum Msg {
+    Host,
+    Typed(String),
+    Join,
+    Net(NetEvent),
+    Report(Box<Report>),
+    Leave,
+}
+
+// Lobby first; then my board next to a live copy of the opponent's. The
+// host picks the seed, whoever joins takes it from the host's Sync.
+pub struct Online {
+    typed: String,
+    error: Option<String>,
+    relay: Option<Relay>,
+    seed: Option<u64>,
+    mine: Option<State>,
+    theirs: Option<State>,
+}
+
+impl Online {
+    fn send(&self, wire: &Wire) {
+        if let Some(relay) = &self.relay {
+            relay.send(wire);
+        }
+    }
+
+    fn catch_up(&mut self, seed: Option<u64>, state: Option<State>) {
+        if self.seed.is_none() {
+            self.seed = seed;
+        }
+        if state.is_some() {
+            self.theirs = state;
+        }
+    }
+
+    fn enter(&mut self, ctx: &Context<Self>, room: String, seed: Option<u64>) {
+        self.relay = Some(Relay::join(room, ctx.link().callback(Msg::Net)));
+        self.seed = seed;
+        self.error = None;
+    }
+
+    fn status_text(&self, relay: &Relay) -> String {
+        match relay.status {
+            Status::Connected if self.theirs.is_none() => "已连接，等对手…".to_string(),
+            status => status.text(),
+        }
+    }
+
+    fn lobby(&self, ctx: &Context<Self>) -> Html {
+        let link = ctx.link();
+        let oninput = link.callback(|event: yew::events::InputEvent| Msg::Typed(event.target_unchecked_into::<HtmlInputElement>().value()));
+        html! {
+            <div class="lobby">
+            <button onclick={link.callback(|_| Msg::Host)}>{ "开房间" }</button>
+            <p>
+            <input placeholder="房间码" maxlength={net::CODE_LENGTH.to_string()} value={self.typed.clone()} {oninput} />
+            <button onclick={link.callback(|_| Msg::Join)}>{ "加入" }</button>
+            </p>
+            if let Some(error) = &self.error {
+                <p>{ error }</p>
+            }
+            </div>
+        }
+    }
+}
+
+impl Component for Online {
+    type Message = Msg;
+    type Properties = ();
+
+    fn create(_ctx: &Context<Self>) -> Self {
+        Self {
+            typed: String::new(),
+            error: None,
+            relay: None,
+            seed: None,
+            mine: None,
+            theirs: None,
+        }
+    }
+
+    fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
+        match msg {
+            Msg::Host => {
+                self.enter(ctx, net::join_code(), Some(Rng::random_seed()));
+                true
+            }
+            Msg::Typed(typed) => {
+                self.typed = typed;
+                false
+            }
+            Msg::Join => {
+                match net::normalize_code(&self.typed) {
+                    Some(room) => self.enter(ctx, room, None),
+                    None => self.error = Some(format!("房间码是{}位字母或数字", net::CODE_LENGTH)),
+                }
+                true
+            }
+            Msg::Net(event) => {
+                if let Some(relay) = &mut self.relay {
+                    relay.handle(&event);
+                }
+                match event {
+                    NetEvent::Open => self.send(&Wire::Hello { seed: self.seed, state: self.mine }),
+                    NetEvent::Message(wire) => match *wire {
+                        Wire::Hello { seed, state } => {
+                            self.catch_up(seed, state);
+                            self.send(&Wire::Sync { seed: self.seed, state: self.mine });
+                        }
+                        Wire::Sync { seed, state } => self.catch_up(seed, state),
+                        Wire::State(state) => self.theirs = Some(state),
+                        Wire::Frame { .. } => {}
+                    },
+                    NetEvent::Closed | NetEvent::Retry => {}
+                }
+                true
+            }
+            Msg::Report(report) => {
+                let state = State { board: report.board, score: report.score, over: report.over };
+                self.mine = Some(state);
+                self.send(&Wire::State(state));
+                false
+            }
+            Msg::Leave => {
+                self.relay = None;
+                self.seed = None;
+                self.mine = None;
+                self.theirs = None;
+                true
+            }
+        }
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        let link = ctx.link();
+        let relay = match &self.relay {
+            Some(relay) => relay,
+            None => return self.lobby(ctx),
+        };
+        html! {
+            <div class="online">
+            <p>{ format!("房间码 {} · {}", relay.room(), self.status_text(relay)) }
+            <button onclick={link.callback(|_| Msg::Leave)}>{ "离开" }</button>
+            </p>
+            <div class="race-boards">
+            <div class="race-seat">
+            <p>{ "我" }</p>
+            if let Some(seed) = self.seed {
+                <GameState key={seed.to_string()} ..GameProps { instance: "online".to_string(), seed: Some(seed), onreport: link.callback(|report| Msg::Report(Box::new(report))), ..GameProps::default() } />
+            } else {
+                <p>{ "等房主发开局种子…" }</p>
+            }
+            </div>
+            <div class="race-seat">
+            if let Some(theirs) = &self.theirs {
+                <p>{ format!("对手：{}分{}", theirs.score, if theirs.over { "，结束" } else { "" }) }</p>
+                <BoardView board={theirs.board} palette={Palette::default()} display={DisplayMode::default()} />
+            } else {
+                <p>{ "对手还没来" }</p>
+            }
+            </div>
+            </div>
+            </div>
+        }
+    }
+}
@@ -0,0 +1,62 @@
+use yew::{html, Callback, Html, MouseEvent};
+
+// performance.now() has sub-millisecond resolution where Date::now() doesn't.
+pub fn now() -> f64 {
+    web_sys::window()
+        .and_then(|w| w.performance())
+        .map(|p| p.now())
+        .unwrap_or_else(js_sys::Date::now)
+}
+
+// Runs `f` and says how long it took, in milliseconds.
+pub fn timed<T>(f: impl FnOnce() -> T) -> (T, f64) {
+    let start = now();
+    let value = f();
+    (value, now() - start)
+}
+
+// Counted whether the HUD shows or not, so turning it on mid-game gives real
+// numbers straight away.
+#[derive(Debug, Default)]
+pub struct Perf {
+    pub shown: bool,
+    update_ms: f64,
+    renders: u64,
+    solver: Option<(&'static str, f64)>,
+}
+
+impl Perf {
+    pub fn updated(&mut self, ms: f64) {
+        self.update_ms = ms;
+    }
+
+    pub fn rendered(&mut self) {
+        self.renders += 1;
+    }
+
+    pub fn solved(&mut self, what: &'static str, ms: f64) {
+        self.solver = Some((what, ms));
+    }
+
+    pub fn view(&self) -> Html {
+        if !self.shown {
+            return html! {};
+        }
+        html! {
+            <div class="perf-hud" aria-hidden="true">
+            <div>{ format!("上次更新 {:.2}ms", self.update_ms) }</div>
+            <div>{ format!("已渲染 {}次", self.renders) }</div>
+            <div>{ match self.solver {
+                Some((what, ms)) => format!("{} {:.2}ms", what, ms),
+                None => "求解器还没跑过".to_string(),
+            } }</div>
+            </div>
+        }
+    }
+}
+
+pub fn toggle(shown: bool, ontoggle: Callback<MouseEvent>) -> Html {
+    html! {
+        <p><label><input type="checkbox" checked={shown} onclick={ontoggle} />{ "性能面板" }</label></p>
+    }
+}
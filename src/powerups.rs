@@ -0,0 +1,115 @@
+use serde::{Deserialize, Serialize};
+use yew::{html, Callback, Html};
+
+use crate::board::Position;
+
+const BOMB_EVERY: u64 = 20;
+const SWAP_EVERY: u64 = 30;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PowerUp {
+    Bomb,
+    Swap,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Selection {
+    Bomb,
+    SwapFirst,
+    SwapSecond(Position),
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct PowerUps {
+    bombs: u32,
+    swaps: u32,
+    merges: u64,
+}
+
+impl PowerUp {
+    pub const ALL: [PowerUp; 2] = [PowerUp::Bomb, PowerUp::Swap];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            PowerUp::Bomb => "炸弹",
+            PowerUp::Swap => "交换",
+        }
+    }
+
+    fn every(&self) -> u64 {
+        match self {
+            PowerUp::Bomb => BOMB_EVERY,
+            PowerUp::Swap => SWAP_EVERY,
+        }
+    }
+}
+
+impl Selection {
+    pub fn start(power_up: PowerUp) -> Self {
+        match power_up {
+            PowerUp::Bomb => Selection::Bomb,
+            PowerUp::Swap => Selection::SwapFirst,
+        }
+    }
+
+    pub fn power_up(&self) -> PowerUp {
+        match self {
+            Selection::Bomb => PowerUp::Bomb,
+            Selection::SwapFirst | Selection::SwapSecond(_) => PowerUp::Swap,
+        }
+    }
+}
+
+impl PowerUps {
+    pub fn record_merges(&mut self, merges: u64) {
+        for merge in self.merges + 1..=self.merges + merges {
+            if merge % BOMB_EVERY == 0 {
+                self.bombs += 1;
+            }
+            if merge % SWAP_EVERY == 0 {
+                self.swaps += 1;
+            }
+        }
+        self.merges += merges;
+    }
+
+    pub fn available(&self, power_up: PowerUp) -> u32 {
+        match power_up {
+            PowerUp::Bomb => self.bombs,
+            PowerUp::Swap => self.swaps,
+        }
+    }
+
+    pub fn consume(&mut self, power_up: PowerUp) {
+        match power_up {
+            PowerUp::Bomb => self.bombs = self.bombs.saturating_sub(1),
+            PowerUp::Swap => self.swaps = self.swaps.saturating_sub(1),
+        }
+    }
+
+    pub fn view(&self, selecting: Option<Selection>, onselect: Callback<PowerUp>) -> Html {
+        html! {
+            <div class="powerups">
+            { PowerUp::ALL.iter().map(|power_up| {
+                let power_up = *power_up;
+                let class = if selecting.map(|s| s.power_up()) == Some(power_up) { "selected" } else { "" };
+                let every = power_up.every();
+                html! {
+                    <span>
+                    <button {class} disabled={self.available(power_up) == 0} onclick={onselect.reform(move |_| power_up)}>
+                    { format!("{} ×{}", power_up.label(), self.available(power_up)) }
+                    </button>
+                    <small>{ format!("还差{}次合并", every - self.merges % every) }</small>
+                    </span>
+                }
+            }).collect::<Html>() }
+            if selecting == Some(Selection::SwapFirst) {
+                <p>{ "选第一个方块" }</p>
+            }
+            if let Some(Selection::SwapSecond(_)) = selecting {
+                <p>{ "再选一个方块和它交换" }</p>
+            }
+            </div>
+        }
+    }
+}
@@ -0,0 +1,52 @@
+use web_sys::PointerEvent;
+use yew::{html, Callback, Html};
+
+use crate::board::{Board, Direction};
+use crate::board_view::BoardView;
+use crate::rules::Rules;
+use crate::settings::Action;
+use crate::theme::{DisplayMode, Palette};
+
+// A press held at least this long on the pad only previews; shorter is a move.
+pub const LONG_PRESS_MS: f64 = 400.0;
+
+// The board after sliding toward `direction`, before anything spawns; None
+// when nothing would slide.
+pub fn after(board: &Board, rules: &dyn Rules, direction: Direction) -> Option<Board> {
+    let mut after = *board;
+    let events = after.slide(rules, direction);
+    (!events.is_empty()).then_some(after)
+}
+
+pub fn view(board: &Board, rules: &dyn Rules, direction: Direction, palette: Palette, display: DisplayMode) -> Html {
+    let label = Action::Move(direction).label();
+    html! {
+        <div class="ghost move-preview" aria-live="polite">
+        if let Some(after) = after(board, rules, direction) {
+            <p>{ format!("往{}走会变成这样（还没出新方块）", label) }</p>
+            <BoardView board={after} {palette} {display} />
+        } else {
+            <p>{ format!("往{}走不动", label) }</p>
+        }
+        </div>
+    }
+}
+
+// On-screen arrows. Hovering one previews it; a tap or click moves; a long
+// press previews until it lets go.
+pub fn pad(onhover: Callback<Option<Direction>>, onpress: Callback<Direction>, onrelease: Callback<(Direction, bool)>) -> Html {
+    html! {
+        <div class="pad">
+        { Direction::ALL.iter().map(|direction| {
+            let direction = *direction;
+            let onpointerenter = onhover.reform(move |event: PointerEvent| (event.pointer_type() == "mouse").then_some(direction));
+            let onpointerleave = onhover.reform(|_: PointerEvent| None);
+            let onpointerdown = onpress.reform(move |_: PointerEvent| direction);
+            let onpointerup = onrelease.reform(move |event: PointerEvent| (direction, event.pointer_type() == "mouse"));
+            html! {
+                <button {onpointerenter} {onpointerleave} {onpointerdown} {onpointerup}>{ Action::Move(direction).label() }</button>
+            }
+        }).collect::<Html>() }
+        </div>
+    }
+}
@@ -0,0 +1,264 @@
+use std::collections::BTreeSet;
+
+use gloo::storage::{LocalStorage, Storage};
+use serde::{Deserialize, Serialize};
+use web_sys::HtmlInputElement;
+use yew::{html, Callback, Event, Html, MouseEvent, TargetCast};
+
+use crate::board::{Board, Cell, Position, MAX_SIDE};
+use crate::handoff;
+use crate::storage::Namespace;
+use crate::theme::DisplayMode;
+
+// Puzzles live in puzzles.json. Each row is a line of squares separated by
+// spaces: a number, . for empty or # for a wall. The seed fixes the tiles
+// that spawn, so a puzzle plays the same every time.
+const MANIFEST: &str = include_str!("puzzles.json");
+const SOLVED_KEY: &str = "puzzles";
+const MAX_MOVES: u64 = 999;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Puzzle {
+    // Shared puzzles get theirs from the link's checksum.
+    #[serde(default)]
+    pub id: String,
+    pub name: String,
+    rows: Vec<String>,
+    pub target: u64,
+    pub moves: u64,
+    pub seed: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PuzzleError {
+    Malformed,
+    Checksum,
+    Invalid,
+}
+
+impl PuzzleError {
+    pub fn message(&self) -> &'static str {
+        match self {
+            PuzzleError::Malformed => "残局链接看不懂",
+            PuzzleError::Checksum => "残局链接抄错了",
+            PuzzleError::Invalid => "这道题不成立：目标得比棋盘上的方块都大，步数在1到999之间",
+        }
+    }
+}
+
+// What the authoring form holds; the target is as the board shows it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Draft {
+    pub name: String,
+    pub target: u64,
+    pub moves: u64,
+}
+
+impl Default for Draft {
+    fn default() -> Self {
+        Self { name: String::new(), target: 256, moves: 10 }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Status {
+    Solving { left: u64 },
+    Solved,
+    Failed,
+}
+
+impl Puzzle {
+    pub fn from_board(board: &Board, draft: &Draft, display: DisplayMode, seed: u64) -> Self {
+        let rows = (0..board.rows()).map(|row| {
+            (0..board.columns()).map(|column| match board[Position { row, column }] {
+                Cell::Empty => ".".to_string(),
+                Cell::Wall => "#".to_string(),
+                Cell::Value(v) => v.to_string(),
+            }).collect::<Vec<_>>().join(" ")
+        }).collect();
+        let name = match draft.name.trim() {
+            "" => "自制残局".to_string(),
+            name => name.to_string(),
+        };
+        Self { id: String::new(), name, rows, target: display.raw(draft.target), moves: draft.moves, seed }
+    }
+
+    // Solvable on its face: something to reach and some moves to reach it in.
+    fn valid(&self) -> bool {
+        self.board().is_some_and(|board| board.highest() < self.target) && (1..=MAX_MOVES).contains(&self.moves)
+    }
+
+    // Base64 JSON plus a checksum, the same shape as a handoff code. btoa only
+    // takes bytes, and names are usually Chinese, so the JSON goes in as UTF-8.
+    pub fn encode(&self) -> Result<String, PuzzleError> {
+        if !self.valid() {
+            return Err(PuzzleError::Invalid);
+        }
+        let json = serde_json::to_string(&Puzzle { id: String::new(), ..self.clone() }).map_err(|_| PuzzleError::Malformed)?;
+        let bytes: String = json.bytes().map(char::from).collect();
+        let encoded = web_sys::window().and_then(|w| w.btoa(&bytes).ok()).ok_or(PuzzleError::Malformed)?;
+        Ok(format!("{}-{:08x}", encoded, handoff::checksum(&json)))
+    }
+
+    pub fn decode(code: &str) -> Result<Self, PuzzleError> {
+        let (encoded, sum) = code.trim().rsplit_once('-').ok_or(PuzzleError::Malformed)?;
+        let sum = u32::from_str_radix(sum, 16).map_err(|_| PuzzleError::Malformed)?;
+        let bytes = web_sys::window()
+            .and_then(|w| w.atob(encoded).ok())
+            .ok_or(PuzzleError::Malformed)?;
+        let json = String::from_utf8(bytes.chars().map(|c| c as u8).collect()).map_err(|_| PuzzleError::Malformed)?;
+        if handoff::checksum(&json) != sum {
+            return Err(PuzzleError::Checksum);
+        }
+        let puzzle: Puzzle = serde_json::from_str(&json).map_err(|_| PuzzleError::Malformed)?;
+        if !puzzle.valid() {
+            return Err(PuzzleError::Invalid);
+        }
+        Ok(Puzzle { id: format!("custom-{:08x}", sum), ..puzzle })
+    }
+
+    pub fn board(&self) -> Option<Board> {
+        let rows: Vec<Vec<&str>> = self.rows.iter().map(|row| row.split_whitespace().collect()).collect();
+        let columns = rows.first()?.len();
+        if !(1..=MAX_SIDE as usize).contains(&rows.len()) || !(1..=MAX_SIDE as usize).contains(&columns) {
+            return None;
+        }
+        let mut board = Board::new(rows.len() as u8, columns as u8);
+        for (row, squares) in rows.iter().enumerate() {
+            if squares.len() != columns {
+                return None;
+            }
+            for (column, square) in squares.iter().enumerate() {
+                let cell = match *square {
+                    "." => Cell::Empty,
+                    "#" => Cell::Wall,
+                    value => Cell::Value(value.parse().ok().filter(|v| *v > 0)?),
+                };
+                board.set(Position { row: row as u8, column: column as u8 }.position(), cell);
+            }
+        }
+        Some(board)
+    }
+
+    pub fn objective(&self, display: DisplayMode) -> String {
+        format!("{}步内合出{}", self.moves, display.format(self.target))
+    }
+
+    // Only the board and move count go in, so an undo reopens a failed puzzle.
+    pub fn status(&self, board: &Board, moves: u64) -> Status {
+        if board.highest() >= self.target {
+            Status::Solved
+        } else if moves >= self.moves {
+            Status::Failed
+        } else {
+            Status::Solving { left: self.moves - moves }
+        }
+    }
+}
+
+// Puzzles that don't describe a board are left out rather than failing the lot.
+pub fn manifest() -> Vec<Puzzle> {
+    let puzzles: Vec<Puzzle> = serde_json::from_str(MANIFEST).unwrap_or_else(|e| {
+        log::warn!("Failed to parse puzzles: {:?}", e);
+        Vec::new()
+    });
+    puzzles.into_iter()
+        .filter(|puzzle| {
+            let valid = puzzle.board().is_some();
+            if !valid {
+                log::warn!("Skipping puzzle {} with an unreadable board", puzzle.id);
+            }
+            valid
+        })
+        .collect()
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Solved {
+    ids: BTreeSet<String>,
+    #[serde(skip)]
+    namespace: Namespace,
+}
+
+impl Solved {
+    pub fn load(namespace: &Namespace) -> Self {
+        let solved: Self = LocalStorage::get(namespace.key(SOLVED_KEY)).unwrap_or_default();
+        Self { namespace: namespace.clone(), ..solved }
+    }
+
+    fn save(&self) {
+        if let Err(e) = LocalStorage::set(self.namespace.key(SOLVED_KEY), self) {
+            log::warn!("Failed to save solved puzzles: {:?}", e);
+        }
+    }
+
+    pub fn record(&mut self, puzzle: &Puzzle) {
+        if self.ids.insert(puzzle.id.clone()) {
+            self.save();
+        }
+    }
+}
+
+pub fn link(code: &str) -> String {
+    let location = web_sys::window().map(|w| w.location());
+    let base = location
+        .and_then(|l| Some(format!("{}{}", l.origin().ok()?, l.pathname().ok()?)))
+        .unwrap_or_default();
+    format!("{}?puzzle={}", base, js_sys::encode_uri_component(code))
+}
+
+pub struct Authoring<'a> {
+    pub draft: &'a Draft,
+    pub link: Option<&'a str>,
+    pub error: Option<&'a str>,
+}
+
+pub fn view(puzzles: &[Puzzle], solved: &Solved, display: DisplayMode, authoring: Authoring, onpick: Callback<usize>, ondraft: Callback<Draft>, onshare: Callback<MouseEvent>) -> Html {
+    let draft = authoring.draft;
+    let value = |event: Event| event.target_unchecked_into::<HtmlInputElement>().value();
+    let onname = {
+        let draft = draft.clone();
+        ondraft.reform(move |event: Event| Draft { name: value(event), ..draft.clone() })
+    };
+    let ontarget = {
+        let draft = draft.clone();
+        ondraft.reform(move |event: Event| Draft { target: value(event).parse().unwrap_or(draft.target), ..draft.clone() })
+    };
+    let onmoves = {
+        let draft = draft.clone();
+        ondraft.reform(move |event: Event| Draft { moves: value(event).parse().unwrap_or(draft.moves), ..draft.clone() })
+    };
+    html! {
+        <div class="puzzles">
+        <h3>{ "残局" }</h3>
+        <p>{ "摆好的局面，限定步数内合出目标方块。不计入高分榜和成就。" }</p>
+        <table class="stats">
+        { puzzles.iter().enumerate().map(|(i, puzzle)| html! {
+            <tr>
+            <td>{ if solved.ids.contains(&puzzle.id) { "✓" } else { "" } }</td>
+            <td>{ &puzzle.name }</td>
+            <td>{ puzzle.objective(display) }</td>
+            <td><button onclick={onpick.reform(move |_| i)}>{ "开始" }</button></td>
+            </tr>
+        }).collect::<Html>() }
+        </table>
+        <h4>{ "出题" }</h4>
+        <p>{ "先用“摆盘”摆好棋盘，再定个目标，生成的链接发给别人就能玩同一道题。" }</p>
+        <p>
+        <input type="text" placeholder="题目名字" value={draft.name.clone()} onchange={onname} />
+        { "目标" }
+        <input type="number" min="2" value={draft.target.to_string()} onchange={ontarget} />
+        { "步数" }
+        <input type="number" min="1" max={MAX_MOVES.to_string()} value={draft.moves.to_string()} onchange={onmoves} />
+        <button onclick={onshare}>{ "生成链接" }</button>
+        </p>
+        if let Some(link) = authoring.link {
+            <textarea readonly=true rows="3" cols="40" value={link.to_string()} />
+        }
+        if let Some(error) = authoring.error {
+            <p class="warning">{ error }</p>
+        }
+        </div>
+    }
+}
@@ -0,0 +1,59 @@
+use gloo::events::{EventListener, EventListenerOptions};
+use js_sys::{Function, Promise, Reflect};
+use wasm_bindgen::{JsCast, JsValue};
+use wasm_bindgen_futures::JsFuture;
+use web_sys::Event;
+use yew::Callback;
+
+// Copied next to index.html by trunk; caches the build for offline play.
+const SERVICE_WORKER: &str = "sw.js";
+
+// navigator.serviceWorker and BeforeInstallPromptEvent aren't in the web-sys
+// features this crate builds with, and the first is missing outside secure
+// contexts anyway, hence the reflection.
+fn call(target: &JsValue, name: &str, args: &[JsValue]) -> Option<JsValue> {
+    let method = Reflect::get(target, &JsValue::from_str(name)).ok()?.dyn_into::<Function>().ok()?;
+    let args: js_sys::Array = args.iter().collect();
+    method.apply(target, &args).ok()
+}
+
+pub fn register() {
+    let container = web_sys::window()
+        .and_then(|w| Reflect::get(&w.navigator(), &JsValue::from_str("serviceWorker")).ok())
+        .filter(|container| !container.is_undefined());
+    let registration = container
+        .and_then(|container| call(&container, "register", &[JsValue::from_str(SERVICE_WORKER)]))
+        .and_then(|promise| promise.dyn_into::<Promise>().ok());
+    match registration {
+        Some(promise) => wasm_bindgen_futures::spawn_local(async move {
+            if let Err(e) = JsFuture::from(promise).await {
+                log::warn!("Failed to register the service worker: {:?}", e);
+            }
+        }),
+        None => log::info!("No service worker support; the game won't load offline"),
+    }
+}
+
+// The browser offers installing once it's satisfied the app works offline.
+// Holding on to that event stops its own banner and lets the game's button
+// show the prompt instead.
+pub fn listen(onavailable: Callback<Event>, oninstalled: Callback<()>) -> Vec<EventListener> {
+    let window = match web_sys::window() {
+        Some(window) => window,
+        None => return Vec::new(),
+    };
+    vec![
+        EventListener::new_with_options(&window, "beforeinstallprompt", EventListenerOptions::enable_prevent_default(), move |event| {
+            event.prevent_default();
+            onavailable.emit(event.clone());
+        }),
+        EventListener::new(&window, "appinstalled", move |_| oninstalled.emit(())),
+    ]
+}
+
+// A prompt event only shows once; the caller drops it afterwards.
+pub fn prompt(event: &Event) {
+    if call(event, "prompt", &[]).is_none() {
+        log::warn!("Failed to show the install prompt");
+    }
+}
@@ -0,0 +1,192 @@
+use yew::{html, Component, Context, Html};
+
+use crate::board::{Board, MoveResult};
+use crate::flags;
+use crate::game_view_2048::{GameProps, GameState};
+use crate::rng::Rng;
+use crate::settings::KeyBindings;
+
+// In a battle every merge into at least this much (128 as usually shown)
+// drops a junk tile on the other board.
+const GARBAGE_FROM: u64 = 64;
+pub const JUNK: u64 = 1;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Seat {
+    Left,
+    Right,
+}
+
+impl Seat {
+    const ALL: [Seat; 2] = [Seat::Left, Seat::Right];
+
+    fn label(&self) -> &'static str {
+        match self {
+            Seat::Left => "左边（WASD）",
+            Seat::Right => "右边（方向键）",
+        }
+    }
+
+    fn index(&self) -> usize {
+        match self {
+            Seat::Left => 0,
+            Seat::Right => 1,
+        }
+    }
+
+    fn opponent(&self) -> Seat {
+        match self {
+            Seat::Left => Seat::Right,
+            Seat::Right => Seat::Left,
+        }
+    }
+
+    // Only moves are bound: undo, restart and the rest would spoil a race.
+    pub fn key_bindings(&self) -> KeyBindings {
+        KeyBindings::moves(match self {
+            Seat::Left => ["w", "a", "s", "d"],
+            Seat::Right => ["ArrowUp", "ArrowLeft", "ArrowDown", "ArrowRight"],
+        })
+    }
+}
+
+// What a board tells its parent after every render. Attacks count up over
+// the game; the race forwards the new ones.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Report {
+    pub board: Board,
+    pub score: u64,
+    pub won: bool,
+    pub over: bool,
+    pub attacks: u32,
+}
+
+pub fn attacks(turn: &MoveResult) -> u32 {
+    turn.merges.iter().filter(|merge| merge.value >= GARBAGE_FROM).count() as u32
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Verdict {
+    Winner(Seat),
+    Tie,
+}
+
+pub enum Msg {
+    Report(Seat, Box<Report>),
+    Rematch,
+}
+
+// Two boards on one keyboard with the same seed, so both see the same spawns
+// for as long as they play the same moves.
+pub struct Race {
+    seed: u64,
+    round: u32,
+    reports: [Report; 2],
+    verdict: Option<Verdict>,
+    // ?race=battle: big merges send junk across, kept as a running total per board.
+    battle: bool,
+    garbage: [u32; 2],
+}
+
+impl Race {
+    // Reaching the goal first wins outright; otherwise it goes to the score
+    // once both boards are stuck.
+    fn judge(&self) -> Option<Verdict> {
+        let [left, right] = self.reports;
+        match (left.won, right.won) {
+            (true, false) => return Some(Verdict::Winner(Seat::Left)),
+            (false, true) => return Some(Verdict::Winner(Seat::Right)),
+            (false, false) if !(left.over && right.over) => return None,
+            _ => {}
+        }
+        Some(match left.score.cmp(&right.score) {
+            std::cmp::Ordering::Greater => Verdict::Winner(Seat::Left),
+            std::cmp::Ordering::Less => Verdict::Winner(Seat::Right),
+            std::cmp::Ordering::Equal => Verdict::Tie,
+        })
+    }
+}
+
+impl Component for Race {
+    type Message = Msg;
+    type Properties = ();
+
+    fn create(_ctx: &Context<Self>) -> Self {
+        Self {
+            seed: Rng::random_seed(),
+            round: 0,
+            reports: [Report::default(); 2],
+            verdict: None,
+            battle: flags::query_param("race").as_deref() == Some("battle"),
+            garbage: [0; 2],
+        }
+    }
+
+    fn update(&mut self, _ctx: &Context<Self>, msg: Self::Message) -> bool {
+        match msg {
+            Msg::Report(seat, report) => {
+                let sent = report.attacks - self.reports[seat.index()].attacks;
+                self.reports[seat.index()] = *report;
+                let attacked = self.battle && sent > 0 && self.verdict.is_none();
+                if attacked {
+                    self.garbage[seat.opponent().index()] += sent;
+                }
+                if self.verdict.is_none() {
+                    self.verdict = self.judge();
+                }
+                attacked || self.verdict.is_some()
+            }
+            Msg::Rematch => {
+                self.seed = Rng::random_seed();
+                self.round += 1;
+                self.reports = [Report::default(); 2];
+                self.verdict = None;
+                self.garbage = [0; 2];
+                true
+            }
+        }
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        let link = ctx.link();
+        html! {
+            <div class="race">
+            <div class="race-boards">
+            { Seat::ALL.iter().map(|seat| {
+                let seat = *seat;
+                let instance = format!("race-{:?}", seat).to_lowercase();
+                let key = format!("{}-{}", instance, self.round);
+                let props = GameProps {
+                    instance,
+                    seat: Some(seat),
+                    seed: Some(self.seed),
+                    garbage: self.garbage[seat.index()],
+                    onreport: link.callback(move |report| Msg::Report(seat, Box::new(report))),
+                    ..GameProps::default()
+                };
+                html! {
+                    <div class="race-seat">
+                    <p>{ seat.label() }
+                    if self.battle {
+                        { format!("，挨了{}个垃圾", self.garbage[seat.index()]) }
+                    }
+                    </p>
+                    <GameState {key} ..props />
+                    </div>
+                }
+            }).collect::<Html>() }
+            </div>
+            if let Some(verdict) = self.verdict {
+                <div class="race-overlay">
+                <p>{ match verdict {
+                    Verdict::Winner(seat) => format!("{}赢了！", seat.label()),
+                    Verdict::Tie => "平局！".to_string(),
+                } }</p>
+                <p>{ format!("{} : {}", self.reports[0].score, self.reports[1].score) }</p>
+                <button onclick={link.callback(|_| Msg::Rematch)}>{ "再来一局" }</button>
+                </div>
+            }
+            </div>
+        }
+    }
+}
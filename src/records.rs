@@ -0,0 +1,52 @@
+use gloo::storage::{LocalStorage, Storage};
+use serde::{Deserialize, Serialize};
+use yew::{html, Html};
+
+use crate::stats::Stats;
+use crate::storage::Namespace;
+
+const RECORDS_KEY: &str = "records";
+
+// Lifetime bests across every counted game, folded in from each game's stats.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Records {
+    pub most_merges_in_move: u64,
+    pub best_move_score: u64,
+    pub longest_combo: u64,
+    #[serde(skip)]
+    namespace: Namespace,
+}
+
+impl Records {
+    pub fn load(namespace: &Namespace) -> Self {
+        let records: Self = LocalStorage::get(namespace.key(RECORDS_KEY)).unwrap_or_default();
+        Self { namespace: namespace.clone(), ..records }
+    }
+
+    fn save(&self) {
+        if let Err(e) = LocalStorage::set(self.namespace.key(RECORDS_KEY), self) {
+            log::warn!("Failed to save records: {:?}", e);
+        }
+    }
+
+    pub fn record(&mut self, stats: &Stats) {
+        let before = (self.most_merges_in_move, self.best_move_score, self.longest_combo);
+        self.most_merges_in_move = self.most_merges_in_move.max(stats.most_merges_in_move);
+        self.best_move_score = self.best_move_score.max(stats.best_move_score);
+        self.longest_combo = self.longest_combo.max(stats.longest_combo);
+        if (self.most_merges_in_move, self.best_move_score, self.longest_combo) != before {
+            self.save();
+        }
+    }
+
+    pub fn view(&self) -> Html {
+        html! {
+            <table class="stats">
+            <tr><td>{ "历史一步最多合并" }</td><td>{ self.most_merges_in_move }</td></tr>
+            <tr><td>{ "历史一步最高得分" }</td><td>{ self.best_move_score }</td></tr>
+            <tr><td>{ "历史最长连击" }</td><td>{ self.longest_combo }</td></tr>
+            </table>
+        }
+    }
+}
@@ -0,0 +1,265 @@
+use gloo::events::EventListener;
+use serde::{Deserialize, Serialize};
+use web_sys::{FileReader, HtmlInputElement};
+use yew::Callback;
+
+use crate::board::{Board, Direction, Event, Position};
+use crate::mode::{self, GameMode};
+use crate::rng::Rng;
+use crate::spawn::SpawnPolicy;
+use crate::summary;
+
+type Spawn = Option<(Position, u64)>;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Step {
+    pub direction: Direction,
+    pub spawned: Spawn,
+}
+
+// A game from its seed: the first tile, then every move with the tile it
+// spawned. The spawns are redundant with the seed, which is what lets a
+// loaded file be checked.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Replay {
+    pub mode: GameMode,
+    // Missing from files saved before there was a choice.
+    #[serde(default)]
+    pub spawn: SpawnPolicy,
+    pub seed: u64,
+    pub start: Spawn,
+    pub steps: Vec<Step>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Divergence {
+    Malformed,
+    Unsupported,
+    Spawn { step: usize, recorded: Spawn, expected: Spawn },
+    Over { step: usize },
+    Blocked { step: usize },
+}
+
+impl Divergence {
+    pub fn message(&self) -> String {
+        match self {
+            Divergence::Malformed => "回放文件看不懂".to_string(),
+            Divergence::Unsupported => "活动模式的回放没法校验".to_string(),
+            Divergence::Spawn { step: 0, recorded, expected } => {
+                format!("开局就对不上：记录里是{}，按种子应该是{}", spawn_text(*recorded), spawn_text(*expected))
+            }
+            Divergence::Spawn { step, recorded, expected } => {
+                format!("第{}步对不上：记录里新方块是{}，按种子应该是{}", step, spawn_text(*recorded), spawn_text(*expected))
+            }
+            Divergence::Over { step } => format!("第{}步不合法：这时游戏已经结束了", step),
+            Divergence::Blocked { step } => format!("第{}步不合法：这个方向推不动", step),
+        }
+    }
+}
+
+fn spawn_text(spawn: Spawn) -> String {
+    match spawn {
+        Some((p, value)) => format!("第{}行第{}列的{}", p.row + 1, p.column + 1, value),
+        None => "没有新方块".to_string(),
+    }
+}
+
+impl Replay {
+    pub fn parse(text: &str) -> Result<Self, Divergence> {
+        let replay: Replay = serde_json::from_str(text).map_err(|_| Divergence::Malformed)?;
+        replay.validate()?;
+        Ok(replay)
+    }
+
+    pub fn validate(&self) -> Result<(), Divergence> {
+        if self.mode == GameMode::Event {
+            return Err(Divergence::Unsupported);
+        }
+        let mut playthrough = Playthrough::new(self);
+        if playthrough.start != self.start {
+            return Err(Divergence::Spawn { step: 0, recorded: self.start, expected: playthrough.start });
+        }
+        for (i, step) in self.steps.iter().enumerate() {
+            let expected = playthrough.advance(step.direction)?;
+            if expected != step.spawned {
+                return Err(Divergence::Spawn { step: i + 1, recorded: step.spawned, expected });
+            }
+        }
+        Ok(())
+    }
+
+    pub fn download(&self) {
+        match serde_json::to_string(self) {
+            Ok(json) => {
+                let url = format!("data:application/json;charset=utf-8,{}", js_sys::encode_uri_component(&json));
+                summary::save(&url, "yew-2048-replay.json");
+            }
+            Err(e) => log::warn!("Failed to export replay: {:?}", e),
+        }
+    }
+}
+
+// A game played again from its seed the way GameState plays it. A step has
+// to slide something, since GameState doesn't record one that doesn't; a
+// stopped win followed by more steps means 继续玩 was picked.
+#[derive(Debug, Clone)]
+pub struct Playthrough {
+    mode: GameMode,
+    spawn: SpawnPolicy,
+    rng: Rng,
+    pub board: Board,
+    pub score: u64,
+    pub played: usize,
+    pub start: Spawn,
+    won: bool,
+    pub lost: bool,
+}
+
+impl Playthrough {
+    pub fn new(replay: &Replay) -> Self {
+        let (mode, spawn) = (replay.mode, replay.spawn);
+        let mut rng = Rng::new(replay.seed);
+        let mut board = mode.board();
+        mode.place_walls(&mut board, &mut rng);
+        let start = spawned(spawn.spawn(&mut board, mode.rules(), &mut rng));
+        Self { mode, spawn, rng, board, score: 0, played: 0, start, won: false, lost: false }
+    }
+
+    // The tile the move spawned.
+    pub fn advance(&mut self, direction: Direction) -> Result<Spawn, Divergence> {
+        self.played += 1;
+        if self.lost {
+            return Err(Divergence::Over { step: self.played });
+        }
+        let rules = self.mode.rules();
+        let events = self.board.slide(rules, direction);
+        if !events.iter().any(Event::moves_tile) {
+            return Err(Divergence::Blocked { step: self.played });
+        }
+        self.score += events.iter().map(Event::score).sum::<u64>();
+        if self.mode.stops_on_win() && !self.won && rules.wins(self.board.highest()) {
+            self.won = true;
+            return Ok(None);
+        }
+        let spawn = spawned(self.spawn.spawn(&mut self.board, rules, &mut self.rng));
+        if self.board.dead(rules) {
+            if self.mode.endless() {
+                for event in mode::endless_clears(&self.board) {
+                    self.board.apply_event(event);
+                }
+            } else {
+                self.lost = true;
+            }
+        }
+        Ok(spawn)
+    }
+}
+
+fn spawned(event: Option<Event>) -> Spawn {
+    match event? {
+        Event::Spawn { position, value } => Some((position, value)),
+        _ => None,
+    }
+}
+
+// Keeps the reader alive until the file has been read.
+pub struct Loader {
+    _reader: FileReader,
+    _listener: EventListener,
+}
+
+pub fn read(input: &HtmlInputElement, on: Callback<String>) -> Option<Loader> {
+    let file = input.files()?.get(0)?;
+    let reader = match FileReader::new() {
+        Ok(reader) => reader,
+        Err(e) => {
+            log::warn!("Failed to create file reader: {:?}", e);
+            return None;
+        }
+    };
+    let listener = {
+        let loaded = reader.clone();
+        EventListener::once(&reader, "load", move |_| {
+            match loaded.result().ok().and_then(|result| result.as_string()) {
+                Some(text) => on.emit(text),
+                None => log::warn!("Chosen file was not text"),
+            }
+        })
+    };
+    if let Err(e) = reader.read_as_text(&file) {
+        log::warn!("Failed to read chosen file: {:?}", e);
+        return None;
+    }
+    Some(Loader { _reader: reader, _listener: listener })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DIRECTIONS: [Direction; 4] = [Direction::Up, Direction::Left, Direction::Down, Direction::Right];
+
+    fn empty(seed: u64) -> Replay {
+        Replay { mode: GameMode::Classic, spawn: SpawnPolicy::Uniform, seed, start: None, steps: Vec::new() }
+    }
+
+    fn slides(board: &Board, direction: Direction) -> bool {
+        board.slide_events(GameMode::Classic.rules(), direction).iter().any(Event::moves_tile)
+    }
+
+    // Plays the first direction that slides, `moves` times, recording what the seed spawns.
+    fn recorded(seed: u64, moves: usize) -> Replay {
+        let mut replay = empty(seed);
+        let mut playthrough = Playthrough::new(&replay);
+        replay.start = playthrough.start;
+        for _ in 0..moves {
+            let direction = DIRECTIONS.into_iter().find(|&d| slides(&playthrough.board, d)).unwrap();
+            let spawned = playthrough.advance(direction).unwrap();
+            replay.steps.push(Step { direction, spawned });
+        }
+        replay
+    }
+
+    #[test]
+    fn recorded_games_validate() {
+        for seed in 0..20 {
+            let replay = recorded(seed, 12);
+            assert_eq!(replay.validate(), Ok(()), "seed {}", seed);
+            assert_eq!(Replay::parse(&serde_json::to_string(&replay).unwrap()), Ok(replay));
+        }
+    }
+
+    #[test]
+    fn divergent_spawns_name_the_step() {
+        let mut replay = recorded(7, 8);
+        let expected = replay.steps[4].spawned;
+        let tampered = expected.map(|(p, value)| (p, value * 2));
+        replay.steps[4].spawned = tampered;
+        assert_eq!(replay.validate(), Err(Divergence::Spawn { step: 5, recorded: tampered, expected }));
+
+        let mut replay = recorded(7, 1);
+        let expected = replay.start;
+        replay.start = None;
+        assert_eq!(replay.validate(), Err(Divergence::Spawn { step: 0, recorded: None, expected }));
+    }
+
+    #[test]
+    fn a_step_that_slides_nothing_diverges() {
+        let (mut replay, direction) = (0..100)
+            .find_map(|seed| {
+                let replay = recorded(seed, 0);
+                let board = Playthrough::new(&replay).board;
+                DIRECTIONS.into_iter().find(|&d| !slides(&board, d)).map(|d| (replay, d))
+            })
+            .unwrap();
+        replay.steps.push(Step { direction, spawned: None });
+        assert_eq!(replay.validate(), Err(Divergence::Blocked { step: 1 }));
+    }
+
+    #[test]
+    fn unreadable_and_event_replays_are_refused() {
+        assert_eq!(Replay::parse("{"), Err(Divergence::Malformed));
+        let event = Replay { mode: GameMode::Event, ..recorded(3, 2) };
+        assert_eq!(event.validate(), Err(Divergence::Unsupported));
+    }
+}
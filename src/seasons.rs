@@ -0,0 +1,86 @@
+use serde::Deserialize;
+
+use crate::board::{Board, Event};
+use crate::rng::Rng;
+use crate::rules::Rules;
+
+// Dates are UTC "MM-DD" and repeat every year; a window may wrap past New Year.
+const MANIFEST: &str = include_str!("seasons.json");
+const LUCKY_ONE_IN: usize = 8;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub enum Mutator {
+    // Every eighth spawn or so comes in one step bigger.
+    Lucky,
+    // Two tiles spawn after every move.
+    Double,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct Season {
+    pub id: String,
+    pub name: String,
+    start: String,
+    end: String,
+    pub skin: String,
+    pub mutator: Mutator,
+}
+
+impl Mutator {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Mutator::Lucky => "偶尔直接出大一号的方块",
+            Mutator::Double => "每步出两个方块",
+        }
+    }
+
+    pub fn spawns(&self, board: &Board, rules: &dyn Rules, rng: &mut Rng) -> Vec<Event> {
+        match self {
+            Mutator::Lucky => {
+                let event = board.spawn_event(rules, rng);
+                let lucky = rng.below(LUCKY_ONE_IN) == 0;
+                event.map(|event| match event {
+                    Event::Spawn { position, value } if lucky => Event::Spawn { position, value: rules.merge(value, value) },
+                    event => event,
+                }).into_iter().collect()
+            }
+            Mutator::Double => {
+                let mut next = *board;
+                (0..2).filter_map(|_| {
+                    let event = next.spawn_event(rules, rng)?;
+                    next.apply_event(event);
+                    Some(event)
+                }).collect()
+            }
+        }
+    }
+}
+
+impl Season {
+    fn contains(&self, month_day: &str) -> bool {
+        if self.start <= self.end {
+            self.start.as_str() <= month_day && month_day <= self.end.as_str()
+        } else {
+            month_day >= self.start.as_str() || month_day <= self.end.as_str()
+        }
+    }
+
+    // One leaderboard per run of the event, named for the year it started in.
+    pub fn bucket(&self, today: &str) -> String {
+        let year: i32 = today.get(..4).and_then(|y| y.parse().ok()).unwrap_or(0);
+        let wrapped = self.start > self.end && today.get(5..).is_some_and(|md| md <= self.end.as_str());
+        format!("{}-{}", self.id, if wrapped { year - 1 } else { year })
+    }
+}
+
+pub fn manifest() -> Vec<Season> {
+    serde_json::from_str(MANIFEST).unwrap_or_else(|e| {
+        log::warn!("Failed to parse seasons manifest: {:?}", e);
+        Vec::new()
+    })
+}
+
+pub fn active(today: &str) -> Option<Season> {
+    let month_day = today.get(5..10)?;
+    manifest().into_iter().find(|season| season.contains(month_day))
+}
@@ -0,0 +1,621 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use gloo::storage::{LocalStorage, Storage};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use web_sys::{HtmlInputElement, HtmlSelectElement};
+use yew::{html, Callback, Event, Html, MouseEvent, TargetCast};
+
+use crate::ai::{Expectimax, MonteCarlo, Solver};
+use crate::board::Direction;
+use crate::haptics;
+use crate::input::Source;
+use crate::mode::GameMode;
+use crate::narration::{self, Locale};
+use crate::spawn::SpawnPolicy;
+use crate::storage::Namespace;
+use crate::theme::{DisplayMode, Palette};
+
+const SETTINGS_KEY: &str = "settings";
+// Saved next to the fields. When a field changes meaning or shape, the
+// current struct becomes the previous version, with a From into its
+// replacement, and this goes up by one.
+const SETTINGS_VERSION: u64 = 2;
+const EXPECTIMAX_DEPTH: u32 = 2;
+const MONTE_CARLO_ROLLOUTS: u32 = 6;
+const MONTE_CARLO_MOVES: u32 = 10;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum Action {
+    Move(Direction),
+    Undo,
+    Redo,
+    Restart,
+    Pause,
+    ReadBoard,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SolverChoice {
+    #[default]
+    Expectimax,
+    MonteCarlo,
+}
+
+// Stored as a list of pairs: JSON object keys have to be strings, and
+// Move(_) doesn't serialize as one.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(from = "Vec<(Action, String)>", into = "Vec<(Action, String)>")]
+pub struct KeyBindings(BTreeMap<Action, String>);
+
+// What this build reads and writes.
+pub type Settings = SettingsV2;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SettingsV2 {
+    pub key_bindings: KeyBindings,
+    pub mode: GameMode,
+    pub min_move_interval_ms: u32,
+    pub confirm_moves: bool,
+    pub large_print: bool,
+    pub simple_mode: bool,
+    pub low_memory: bool,
+    pub show_merge_badges: bool,
+    pub show_tile_age: bool,
+    pub reduced_motion: Option<bool>,
+    pub narration: bool,
+    pub narration_locale: Locale,
+    pub narration_voice: Option<String>,
+    pub muted: bool,
+    pub volume: u32,
+    pub haptics: bool,
+    pub mascot: bool,
+    pub idle_analysis: bool,
+    pub palette: Palette,
+    pub display: DisplayMode,
+    pub demo_rewind: u32,
+    pub solver: SolverChoice,
+    pub spawn_policy: SpawnPolicy,
+    pub turbo: bool,
+    pub turbo_interval_ms: u32,
+    pub disabled_inputs: BTreeSet<Source>,
+    // Where finished games go and the global top list comes from; empty keeps
+    // the leaderboard local.
+    pub leaderboard_endpoint: String,
+    #[serde(skip)]
+    namespace: Namespace,
+}
+
+// Unversioned saves. Bindings were an object keyed by action, which
+// serde_json could only write when no move was bound, so few if any exist;
+// every other field reads as it does in v2.
+#[derive(Debug, Deserialize)]
+struct SettingsV1 {
+    #[serde(default)]
+    key_bindings: serde_json::Map<String, Value>,
+    #[serde(flatten)]
+    rest: serde_json::Map<String, Value>,
+}
+
+#[derive(Serialize)]
+struct Stored<'a> {
+    version: u64,
+    #[serde(flatten)]
+    settings: &'a Settings,
+}
+
+impl Action {
+    pub const ALL: [Action; 9] = [
+        Action::Move(Direction::Up),
+        Action::Move(Direction::Left),
+        Action::Move(Direction::Down),
+        Action::Move(Direction::Right),
+        Action::Undo,
+        Action::Redo,
+        Action::Restart,
+        Action::Pause,
+        Action::ReadBoard,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Action::Move(Direction::Up) => "上",
+            Action::Move(Direction::Down) => "下",
+            Action::Move(Direction::Left) => "左",
+            Action::Move(Direction::Right) => "右",
+            Action::Undo => "悔棋",
+            Action::Redo => "重做",
+            Action::Restart => "重开",
+            Action::Pause => "暂停",
+            Action::ReadBoard => "读棋盘",
+        }
+    }
+}
+
+impl Default for KeyBindings {
+    fn default() -> Self {
+        Self(BTreeMap::from([
+            (Action::Move(Direction::Up), "e".to_string()),
+            (Action::Move(Direction::Left), "s".to_string()),
+            (Action::Move(Direction::Down), "d".to_string()),
+            (Action::Move(Direction::Right), "f".to_string()),
+            (Action::Undo, "u".to_string()),
+            (Action::Redo, "y".to_string()),
+            (Action::Restart, "r".to_string()),
+            (Action::Pause, "p".to_string()),
+            (Action::ReadBoard, "b".to_string()),
+        ]))
+    }
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            key_bindings: KeyBindings::default(),
+            mode: GameMode::default(),
+            min_move_interval_ms: 0,
+            confirm_moves: false,
+            large_print: false,
+            simple_mode: false,
+            low_memory: false,
+            show_merge_badges: false,
+            show_tile_age: false,
+            reduced_motion: None,
+            narration: false,
+            narration_locale: Locale::default(),
+            narration_voice: None,
+            muted: false,
+            volume: 50,
+            haptics: true,
+            mascot: false,
+            idle_analysis: false,
+            palette: Palette::default(),
+            display: DisplayMode::default(),
+            demo_rewind: 5,
+            solver: SolverChoice::default(),
+            spawn_policy: SpawnPolicy::default(),
+            turbo: false,
+            turbo_interval_ms: 150,
+            disabled_inputs: BTreeSet::new(),
+            leaderboard_endpoint: String::new(),
+            namespace: Namespace::default(),
+        }
+    }
+}
+
+impl SolverChoice {
+    pub const ALL: [SolverChoice; 2] = [SolverChoice::Expectimax, SolverChoice::MonteCarlo];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            SolverChoice::Expectimax => "期望搜索（准）",
+            SolverChoice::MonteCarlo => "随机模拟（快）",
+        }
+    }
+
+    pub fn solver(&self) -> Box<dyn Solver> {
+        match self {
+            SolverChoice::Expectimax => Box::new(Expectimax { depth: EXPECTIMAX_DEPTH }),
+            SolverChoice::MonteCarlo => Box::new(MonteCarlo { rollouts: MONTE_CARLO_ROLLOUTS, moves: MONTE_CARLO_MOVES }),
+        }
+    }
+}
+
+impl From<Vec<(Action, String)>> for KeyBindings {
+    fn from(pairs: Vec<(Action, String)>) -> Self {
+        Self(pairs.into_iter().collect())
+    }
+}
+
+impl From<KeyBindings> for Vec<(Action, String)> {
+    fn from(bindings: KeyBindings) -> Self {
+        bindings.0.into_iter().collect()
+    }
+}
+
+impl KeyBindings {
+    pub fn action(&self, key: &str) -> Option<Action> {
+        let key = normalize(key);
+        self.0.iter().find(|(_, k)| **k == key).map(|(a, _)| *a)
+    }
+
+    pub fn key(&self, action: Action) -> String {
+        self.0.get(&action).map(|k| k.to_uppercase()).unwrap_or_default()
+    }
+
+    // Saves from before an action existed get its default key, unless that key is taken.
+    fn fill_defaults(&mut self) {
+        for (action, key) in KeyBindings::default().0 {
+            if !self.0.contains_key(&action) && !self.0.values().any(|k| *k == key) {
+                self.0.insert(action, key);
+            }
+        }
+    }
+
+    // Up, left, down, right and nothing else, e.g. one player's half of a shared keyboard.
+    pub fn moves(keys: [&str; 4]) -> Self {
+        Self(Action::ALL.iter().zip(keys).map(|(action, key)| (*action, normalize(key))).collect())
+    }
+
+    pub fn bind(&mut self, action: Action, key: &str) {
+        let key = normalize(key);
+        self.0.retain(|_, k| *k != key);
+        self.0.insert(action, key);
+    }
+}
+
+impl From<SettingsV1> for SettingsV2 {
+    fn from(v1: SettingsV1) -> Self {
+        let mut settings = lenient(Value::Object(v1.rest));
+        for (action, key) in v1.key_bindings {
+            match (serde_json::from_value(Value::String(action.clone())), key.as_str()) {
+                (Ok(action), Some(key)) => settings.key_bindings.bind(action, key),
+                _ => log::warn!("Dropping unreadable binding for {}", action),
+            }
+        }
+        settings
+    }
+}
+
+// Reads a save from any version, walking older ones up a version at a time.
+fn migrate(stored: Value) -> Settings {
+    match stored.get("version").and_then(Value::as_u64).unwrap_or(1) {
+        ..=1 => match serde_json::from_value::<SettingsV1>(stored) {
+            Ok(v1) => v1.into(),
+            Err(e) => {
+                log::warn!("Failed to read old settings: {:?}", e);
+                Settings::default()
+            }
+        },
+        SETTINGS_VERSION => lenient(stored),
+        version => {
+            log::warn!("Settings were saved by a newer version ({}); keeping what still reads", version);
+            lenient(stored)
+        }
+    }
+}
+
+// One field that no longer reads falls back to its default instead of
+// taking every other preference with it.
+fn lenient(stored: Value) -> Settings {
+    if let Ok(settings) = serde_json::from_value(stored.clone()) {
+        return settings;
+    }
+    let mut kept = serde_json::Map::new();
+    for (name, value) in stored.as_object().into_iter().flatten() {
+        let mut trial = kept.clone();
+        trial.insert(name.clone(), value.clone());
+        if serde_json::from_value::<Settings>(Value::Object(trial)).is_ok() {
+            kept.insert(name.clone(), value.clone());
+        } else {
+            log::warn!("Dropping unreadable setting {}", name);
+        }
+    }
+    serde_json::from_value(Value::Object(kept)).unwrap_or_default()
+}
+
+fn input_value(event: &Event) -> String {
+    event.target_unchecked_into::<HtmlInputElement>().value()
+}
+
+fn normalize(key: &str) -> String {
+    key.to_lowercase()
+}
+
+fn prefers_reduced_motion() -> bool {
+    web_sys::window()
+        .and_then(|w| w.match_media("(prefers-reduced-motion: reduce)").ok().flatten())
+        .map(|query| query.matches())
+        .unwrap_or(false)
+}
+
+impl Settings {
+    pub fn load(namespace: &Namespace) -> Self {
+        let mut settings = match LocalStorage::get::<Value>(namespace.key(SETTINGS_KEY)) {
+            Ok(stored) => migrate(stored),
+            Err(_) => Self::default(),
+        };
+        settings.key_bindings.fill_defaults();
+        Self { namespace: namespace.clone(), ..settings }
+    }
+
+    pub fn save(&self) {
+        let stored = Stored { version: SETTINGS_VERSION, settings: self };
+        if let Err(e) = LocalStorage::set(self.namespace.key(SETTINGS_KEY), stored) {
+            log::warn!("Failed to save settings: {:?}", e);
+        }
+    }
+
+    // None follows the browser's prefers-reduced-motion until the player picks explicitly.
+    pub fn reduces_motion(&self) -> bool {
+        self.reduced_motion.unwrap_or_else(prefers_reduced_motion)
+    }
+
+    // Offline builds drop every network-facing section; simple mode hides them at runtime.
+    pub fn online(&self) -> bool {
+        cfg!(not(feature = "offline")) && !self.simple_mode
+    }
+
+    pub fn global_endpoint(&self) -> Option<String> {
+        (self.online() && !self.leaderboard_endpoint.is_empty()).then(|| self.leaderboard_endpoint.clone())
+    }
+
+    pub fn input_enabled(&self, source: Source) -> bool {
+        !self.disabled_inputs.contains(&source)
+    }
+
+    // A callback that hands back these settings as `set` changes them.
+    fn setter<E: 'static>(&self, onchange: &Callback<Settings>, set: impl Fn(&mut Settings, E) + 'static) -> Callback<E> {
+        let settings = self.clone();
+        onchange.reform(move |event| {
+            let mut settings = settings.clone();
+            set(&mut settings, event);
+            settings
+        })
+    }
+
+    fn toggle(&self, onchange: &Callback<Settings>, field: impl Fn(&mut Settings) -> &mut bool + 'static) -> Callback<MouseEvent> {
+        self.setter(onchange, move |s, _: MouseEvent| {
+            let flag = field(s);
+            *flag = !*flag;
+        })
+    }
+
+    pub fn view(&self, rebinding: Option<Action>, onrebind: Callback<Action>, onreset: Callback<MouseEvent>, onchange: Callback<Settings>) -> Html {
+        let interval = self.setter(&onchange, |s, event: Event| s.min_move_interval_ms = input_value(&event).parse().unwrap_or(0));
+        let confirm = self.toggle(&onchange, |s| &mut s.confirm_moves);
+        let large_print = self.toggle(&onchange, |s| &mut s.large_print);
+        let simple_mode = self.toggle(&onchange, |s| &mut s.simple_mode);
+        let low_memory = self.toggle(&onchange, |s| &mut s.low_memory);
+        let reduced_motion = self.setter(&onchange, |s, _: MouseEvent| s.reduced_motion = Some(!s.reduces_motion()));
+        let narration = self.toggle(&onchange, |s| &mut s.narration);
+        let voice = self.setter(&onchange, |s, event: Event| {
+            let value = event.target_unchecked_into::<HtmlSelectElement>().value();
+            s.narration_voice = (!value.is_empty()).then_some(value);
+        });
+        let muted = self.toggle(&onchange, |s| &mut s.muted);
+        let volume = self.setter(&onchange, |s, event: Event| s.volume = input_value(&event).parse::<u32>().unwrap_or(50).min(100));
+        let haptics = self.toggle(&onchange, |s| &mut s.haptics);
+        let mascot = self.toggle(&onchange, |s| &mut s.mascot);
+        let idle_analysis = self.toggle(&onchange, |s| &mut s.idle_analysis);
+        let merge_badges = self.toggle(&onchange, |s| &mut s.show_merge_badges);
+        let turbo = self.toggle(&onchange, |s| &mut s.turbo);
+        let turbo_interval = self.setter(&onchange, |s, event: Event| s.turbo_interval_ms = input_value(&event).parse().unwrap_or(150).max(50));
+        let demo_rewind = self.setter(&onchange, |s, event: Event| s.demo_rewind = input_value(&event).parse().unwrap_or(5));
+        let leaderboard_endpoint = self.setter(&onchange, |s, event: Event| s.leaderboard_endpoint = input_value(&event).trim().to_string());
+        let tile_age = self.toggle(&onchange, |s| &mut s.show_tile_age);
+
+        html! {
+            <div class="settings">
+            <h3>{ "按键设置" }</h3>
+            <table class="stats">
+            { Action::ALL.iter().map(|action| {
+                let action = *action;
+                let key = if rebinding == Some(action) {
+                    "请按键…".to_string()
+                } else {
+                    self.key_bindings.key(action)
+                };
+                html! {
+                    <tr>
+                    <td>{ action.label() }</td>
+                    <td>{ key }</td>
+                    <td><button onclick={onrebind.reform(move |_| action)}>{ "修改" }</button></td>
+                    </tr>
+                }
+            }).collect::<Html>() }
+            </table>
+            <button onclick={onreset}>{ "恢复默认" }</button>
+            <h3>{ "输入方式" }</h3>
+            <table class="stats">
+            { Source::ALL.iter().map(|source| {
+                let source = *source;
+                let toggle = self.setter(&onchange, move |s, _: MouseEvent| {
+                    if !s.disabled_inputs.remove(&source) {
+                        s.disabled_inputs.insert(source);
+                    }
+                });
+                html! {
+                    <tr>
+                    <td>{ source.label() }</td>
+                    <td><input type="checkbox" checked={self.input_enabled(source)} onclick={toggle} /></td>
+                    </tr>
+                }
+            }).collect::<Html>() }
+            </table>
+            <h3>{ "辅助功能" }</h3>
+            <table class="stats">
+            <tr>
+            <td>{ "两步之间至少间隔（毫秒）" }</td>
+            <td><input type="number" min="0" step="50" value={self.min_move_interval_ms.to_string()} onchange={interval} /></td>
+            </tr>
+            <tr>
+            <td>{ "按两次才生效" }</td>
+            <td><input type="checkbox" checked={self.confirm_moves} onclick={confirm} /></td>
+            </tr>
+            <tr>
+            <td>{ "大字简洁模式" }</td>
+            <td><input type="checkbox" checked={self.large_print} onclick={large_print} /></td>
+            </tr>
+            if cfg!(not(feature = "offline")) {
+                <tr>
+                <td>{ "简单模式（隐藏高分榜等联网功能）" }</td>
+                <td><input type="checkbox" checked={self.simple_mode} onclick={simple_mode} /></td>
+                </tr>
+            }
+            if self.online() {
+                <tr>
+                <td>{ "全球榜地址（留空只用本地榜）" }</td>
+                <td><input type="url" placeholder="https://…" value={self.leaderboard_endpoint.clone()} onchange={leaderboard_endpoint} /></td>
+                </tr>
+            }
+            <tr>
+            <td>{ "省内存（只能悔一步，不记分数曲线）" }</td>
+            <td><input type="checkbox" checked={self.low_memory} onclick={low_memory} /></td>
+            </tr>
+            <tr>
+            <td>{ "朗读每一步" }</td>
+            <td><input type="checkbox" checked={self.narration} onclick={narration} /></td>
+            </tr>
+            if self.narration {
+                <tr>
+                <td>{ "朗读语言" }</td>
+                <td>
+                { Locale::ALL.iter().map(|locale| {
+                    let locale = *locale;
+                    let onclick = self.setter(&onchange, move |s, _: MouseEvent| (s.narration_locale, s.narration_voice) = (locale, None));
+                    html! { <button disabled={locale == self.narration_locale} {onclick}>{ locale.label() }</button> }
+                }).collect::<Html>() }
+                </td>
+                </tr>
+                <tr>
+                <td>{ "声音" }</td>
+                <td>
+                <select onchange={voice}>
+                <option value="" selected={self.narration_voice.is_none()}>{ "默认" }</option>
+                { narration::voices(self.narration_locale).into_iter().map(|name| {
+                    let selected = self.narration_voice.as_deref() == Some(name.as_str());
+                    html! { <option value={name.clone()} {selected}>{ name }</option> }
+                }).collect::<Html>() }
+                </select>
+                </td>
+                </tr>
+            }
+            <tr>
+            <td>{ "减少动画" }</td>
+            <td><input type="checkbox" checked={self.reduces_motion()} onclick={reduced_motion} /></td>
+            </tr>
+            <tr>
+            <td>{ "按住方向键连发" }</td>
+            <td><input type="checkbox" checked={self.turbo} onclick={turbo} /></td>
+            </tr>
+            <tr>
+            <td>{ "连发间隔（毫秒）" }</td>
+            <td><input type="number" min="50" step="50" value={self.turbo_interval_ms.to_string()} disabled={!self.turbo} onchange={turbo_interval} /></td>
+            </tr>
+            </table>
+            <h3>{ "玩法" }</h3>
+            <table class="stats">
+            <tr>
+            <td>{ "新方块出现在" }</td>
+            <td>
+            { SpawnPolicy::ALL.iter().map(|spawn_policy| {
+                let spawn_policy = *spawn_policy;
+                let onclick = self.setter(&onchange, move |s, _: MouseEvent| s.spawn_policy = spawn_policy);
+                html! { <button disabled={spawn_policy == self.spawn_policy} {onclick}>{ spawn_policy.label() }</button> }
+            }).collect::<Html>() }
+            </td>
+            </tr>
+            </table>
+            <h3>{ "声音和震动" }</h3>
+            <table class="stats">
+            <tr>
+            <td>{ "静音" }</td>
+            <td><input type="checkbox" checked={self.muted} onclick={muted} /></td>
+            </tr>
+            <tr>
+            <td>{ "音量" }</td>
+            <td><input type="range" min="0" max="100" step="5" value={self.volume.to_string()} disabled={self.muted} onchange={volume} /></td>
+            </tr>
+            if haptics::available() {
+                <tr>
+                <td>{ "合并时震动" }</td>
+                <td><input type="checkbox" checked={self.haptics} onclick={haptics} /></td>
+                </tr>
+            }
+            </table>
+            <h3>{ "显示" }</h3>
+            <table class="stats">
+            <tr>
+            <td>{ "显示方块合并次数" }</td>
+            <td><input type="checkbox" checked={self.show_merge_badges} onclick={merge_badges} /></td>
+            </tr>
+            <tr>
+            <td>{ "按停留时间变色" }</td>
+            <td><input type="checkbox" checked={self.show_tile_age} onclick={tile_age} /></td>
+            </tr>
+            <tr>
+            <td>{ "数字显示" }</td>
+            <td>
+            { DisplayMode::ALL.iter().map(|display| {
+                let display = *display;
+                let onclick = self.setter(&onchange, move |s, _: MouseEvent| s.display = display);
+                html! { <button disabled={display == self.display} {onclick}>{ display.label() }</button> }
+            }).collect::<Html>() }
+            </td>
+            </tr>
+            <tr>
+            <td>{ "显示吉祥物" }</td>
+            <td><input type="checkbox" checked={self.mascot} onclick={mascot} /></td>
+            </tr>
+            <tr>
+            <td>{ "提示和演示用的AI" }</td>
+            <td>
+            { SolverChoice::ALL.iter().map(|solver| {
+                let solver = *solver;
+                let onclick = self.setter(&onchange, move |s, _: MouseEvent| s.solver = solver);
+                html! { <button disabled={solver == self.solver} {onclick}>{ solver.label() }</button> }
+            }).collect::<Html>() }
+            </td>
+            </tr>
+            <tr>
+            <td>{ "空闲时复盘失误" }</td>
+            <td><input type="checkbox" checked={self.idle_analysis} onclick={idle_analysis} /></td>
+            </tr>
+            <tr>
+            <td>{ "AI演示从几步前接手" }</td>
+            <td><input type="number" min="0" max="64" value={self.demo_rewind.to_string()} onchange={demo_rewind} /></td>
+            </tr>
+            <tr>
+            <td>{ "配色" }</td>
+            <td>
+            { Palette::ALL.iter().map(|palette| {
+                let palette = *palette;
+                let onclick = self.setter(&onchange, move |s, _: MouseEvent| s.palette = palette);
+                html! { <button disabled={palette == self.palette} {onclick}>{ palette.label() }</button> }
+            }).collect::<Html>() }
+            </td>
+            </tr>
+            </table>
+            </div>
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn settings_round_trip() {
+        let mut settings = Settings { muted: true, volume: 20, turbo: true, ..Settings::default() };
+        settings.key_bindings.bind(Action::Move(Direction::Up), "w");
+        settings.key_bindings.bind(Action::Undo, "z");
+        let saved = serde_json::to_string(&settings).unwrap();
+        assert_eq!(serde_json::from_str::<Settings>(&saved).unwrap(), settings);
+
+        let stored = serde_json::to_value(Stored { version: SETTINGS_VERSION, settings: &settings }).unwrap();
+        assert_eq!(stored["version"], SETTINGS_VERSION);
+        assert_eq!(migrate(stored), settings);
+    }
+
+    #[test]
+    fn v1_saves_migrate() {
+        let v1 = r#"{"muted":true,"volume":20,"key_bindings":{"Undo":"z","Teleport":"t"}}"#;
+        let settings = migrate(serde_json::from_str(v1).unwrap());
+        assert!(settings.muted);
+        assert_eq!(settings.volume, 20);
+        assert_eq!(settings.key_bindings.action("z"), Some(Action::Undo));
+        assert_eq!(settings.key_bindings.action("u"), None);
+        assert_eq!(settings.key_bindings.key(Action::Move(Direction::Up)), "E");
+    }
+
+    #[test]
+    fn newer_saves_keep_what_still_reads() {
+        let newer = r#"{"version":99,"muted":true,"volume":"loud","sparkles":3}"#;
+        let settings = migrate(serde_json::from_str(newer).unwrap());
+        assert!(settings.muted);
+        assert_eq!(settings.volume, Settings::default().volume);
+    }
+}
@@ -0,0 +1,54 @@
+use yew::{html, Html};
+
+const WIDTH: f64 = 160.0;
+const HEIGHT: f64 = 40.0;
+const GRAPH_WIDTH: f64 = 320.0;
+const GRAPH_HEIGHT: f64 = 120.0;
+// Room on the left for the top score and underneath for the move count.
+const GRAPH_MARGIN: f64 = 40.0;
+const GRAPH_FOOTER: f64 = 16.0;
+
+fn points(values: &[u64], left: f64, width: f64, height: f64) -> String {
+    let max = values.iter().copied().max().unwrap_or(0).max(1) as f64;
+    let step = width / (values.len() - 1) as f64;
+    values.iter().enumerate()
+        .map(|(i, v)| format!("{:.1},{:.1}", left + i as f64 * step, height - *v as f64 / max * height))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+pub fn view(values: &[u64]) -> Html {
+    if values.len() < 2 {
+        return html! {};
+    }
+
+    html! {
+        <svg class="sparkline" width={WIDTH.to_string()} height={HEIGHT.to_string()}
+            viewBox={format!("0 0 {} {}", WIDTH, HEIGHT)} aria-hidden="true">
+        <polyline points={points(values, 0.0, WIDTH, HEIGHT)} fill="none" stroke="indianred" stroke-width="2" />
+        </svg>
+    }
+}
+
+// The same line with axes, score after every move, for looking back on a run.
+pub fn graph(values: &[u64]) -> Html {
+    if values.len() < 2 {
+        return html! {};
+    }
+
+    let max = values.iter().copied().max().unwrap_or(0);
+    let plot = GRAPH_WIDTH - GRAPH_MARGIN;
+    let bottom = GRAPH_HEIGHT - GRAPH_FOOTER;
+    html! {
+        <svg class="score-graph" width={GRAPH_WIDTH.to_string()} height={GRAPH_HEIGHT.to_string()}
+            viewBox={format!("0 0 {} {}", GRAPH_WIDTH, GRAPH_HEIGHT)} role="img"
+            aria-label={format!("{}步里分数涨到{}", values.len() - 1, max)}>
+        <line x1={GRAPH_MARGIN.to_string()} y1="0" x2={GRAPH_MARGIN.to_string()} y2={bottom.to_string()} stroke="gray" />
+        <line x1={GRAPH_MARGIN.to_string()} y1={bottom.to_string()} x2={GRAPH_WIDTH.to_string()} y2={bottom.to_string()} stroke="gray" />
+        <text x={(GRAPH_MARGIN - 4.0).to_string()} y="10" text-anchor="end">{ max }</text>
+        <text x={(GRAPH_MARGIN - 4.0).to_string()} y={bottom.to_string()} text-anchor="end">{ "0" }</text>
+        <text x={GRAPH_WIDTH.to_string()} y={GRAPH_HEIGHT.to_string()} text-anchor="end">{ format!("{}步", values.len() - 1) }</text>
+        <polyline points={points(values, GRAPH_MARGIN, plot, bottom)} fill="none" stroke="indianred" stroke-width="2" />
+        </svg>
+    }
+}
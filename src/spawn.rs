@@ -0,0 +1,84 @@
+use serde::{Deserialize, Serialize};
+
+use crate::ai;
+use crate::board::{Board, Event, Position};
+use crate::rng::Rng;
+use crate::rules::Rules;
+
+// Where new tiles land. Biased policies weight the empty squares instead of
+// picking one uniformly, for a different feel to the same rules.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SpawnPolicy {
+    #[default]
+    Uniform,
+    Corners,
+    Edges,
+    // Hard mode: each tile goes wherever leaves my best reply looking worst.
+    Evil,
+}
+
+impl SpawnPolicy {
+    pub const ALL: [SpawnPolicy; 4] = [SpawnPolicy::Uniform, SpawnPolicy::Corners, SpawnPolicy::Edges, SpawnPolicy::Evil];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            SpawnPolicy::Uniform => "随便哪里",
+            SpawnPolicy::Corners => "多在角上",
+            SpawnPolicy::Edges => "多在边上",
+            SpawnPolicy::Evil => "专挑最坏的格子（困难）",
+        }
+    }
+
+    fn weight(&self, board: &Board, p: Position) -> usize {
+        let row_edge = p.row == 0 || p.row + 1 == board.rows();
+        let column_edge = p.column == 0 || p.column + 1 == board.columns();
+        match self {
+            SpawnPolicy::Uniform | SpawnPolicy::Evil => 1,
+            SpawnPolicy::Corners if row_edge && column_edge => 8,
+            SpawnPolicy::Corners => 1,
+            SpawnPolicy::Edges if row_edge || column_edge => 3,
+            SpawnPolicy::Edges => 1,
+        }
+    }
+
+    // Uniform goes through the engine so existing seeds keep their tiles.
+    pub fn spawn_event(&self, board: &Board, rules: &dyn Rules, rng: &mut Rng) -> Option<Event> {
+        match self {
+            SpawnPolicy::Uniform => return board.spawn_event(rules, rng),
+            // The value is still random; only the square is chosen.
+            SpawnPolicy::Evil => {
+                if board.empties().is_empty() {
+                    return None;
+                }
+                let value = rules.spawn_value(rng);
+                let position = ai::worst_position(board, rules, value)?;
+                return Some(Event::Spawn { position, value });
+            }
+            _ => {}
+        }
+        let weighted: Vec<(Position, usize)> = board.empties().into_iter()
+            .map(Position::from_index)
+            .map(|p| (p, self.weight(board, p)))
+            .collect();
+        let total: usize = weighted.iter().map(|(_, weight)| weight).sum();
+        if total == 0 {
+            return None;
+        }
+        let mut pick = rng.below(total);
+        let (position, _) = *weighted.iter().find(|(_, weight)| {
+            if pick < *weight {
+                return true;
+            }
+            pick -= weight;
+            false
+        })?;
+        let value = rules.spawn_value(rng);
+        Some(Event::Spawn { position, value })
+    }
+
+    pub fn spawn(&self, board: &mut Board, rules: &dyn Rules, rng: &mut Rng) -> Option<Event> {
+        let event = self.spawn_event(board, rules, rng)?;
+        board.apply_event(event);
+        Some(event)
+    }
+}
@@ -0,0 +1,77 @@
+use std::collections::BTreeMap;
+use serde::{Deserialize, Serialize};
+use yew::{html, Html};
+
+use crate::board::{MoveResult, Position};
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Stats {
+    pub highest_tile: u64,
+    pub total_merges: u64,
+    pub merges_by_value: BTreeMap<u64, u64>,
+    // Keyed by the square's index, for the heatmap.
+    pub merges_by_cell: BTreeMap<usize, u64>,
+    pub largest_merge: u64,
+    pub most_merges_in_move: u64,
+    pub best_move_score: u64,
+    // Moves in a row that merged something.
+    pub combo: u64,
+    pub longest_combo: u64,
+}
+
+impl Stats {
+    // Per-tile counts come from record_merge; this keeps the per-move bests.
+    pub fn record_move(&mut self, result: &MoveResult) {
+        if !result.moved {
+            return;
+        }
+        let merges = result.merges.len() as u64;
+        self.combo = if merges > 0 { self.combo + 1 } else { 0 };
+        self.longest_combo = self.longest_combo.max(self.combo);
+        self.most_merges_in_move = self.most_merges_in_move.max(merges);
+        self.best_move_score = self.best_move_score.max(result.score_delta);
+    }
+
+    pub fn record_merge(&mut self, into: Position, value: u64) {
+        self.total_merges += 1;
+        *self.merges_by_value.entry(value).or_insert(0) += 1;
+        *self.merges_by_cell.entry(into.position()).or_insert(0) += 1;
+        if value > self.largest_merge {
+            self.largest_merge = value;
+        }
+        self.record_tile(value);
+    }
+
+    pub fn merges_at(&self, p: Position) -> u64 {
+        self.merges_by_cell.get(&p.position()).copied().unwrap_or(0)
+    }
+
+    pub fn busiest_cell(&self) -> u64 {
+        self.merges_by_cell.values().copied().max().unwrap_or(0)
+    }
+
+    pub fn record_tile(&mut self, value: u64) {
+        if value > self.highest_tile {
+            self.highest_tile = value;
+        }
+    }
+
+    pub fn view(&self) -> Html {
+        html! {
+            <table class="stats">
+            <tr><td>{ "最大方块" }</td><td>{ self.highest_tile }</td></tr>
+            <tr><td>{ "合并次数" }</td><td>{ self.total_merges }</td></tr>
+            <tr><td>{ "单次最大合并" }</td><td>{ self.largest_merge }</td></tr>
+            <tr><td>{ "一步最多合并" }</td><td>{ self.most_merges_in_move }</td></tr>
+            <tr><td>{ "一步最高得分" }</td><td>{ self.best_move_score }</td></tr>
+            <tr><td>{ "最长连击" }</td><td>{ self.longest_combo }</td></tr>
+            { self.merges_by_value.iter().map(|(value, count)| {
+                html! {
+                    <tr><td>{ format!("合成 {}", value) }</td><td>{ count }</td></tr>
+                }
+            }).collect::<Html>() }
+            </table>
+        }
+    }
+}
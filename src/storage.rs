@@ -0,0 +1,146 @@
+use js_sys::Reflect;
+use wasm_bindgen::JsValue;
+use wasm_bindgen_futures::JsFuture;
+use yew::{html, Callback, Html};
+
+pub const PREFIX: &str = "yew-2048.";
+const LOCAL_STORAGE_LIMIT: usize = 5 * 1024 * 1024;
+const WARN_RATIO: f64 = 0.8;
+
+// Each mounted game gets its own key space; the default instance keeps the
+// original unprefixed keys so existing saves still load.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Namespace(String);
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Estimate {
+    pub usage: f64,
+    pub quota: f64,
+}
+
+impl Namespace {
+    pub fn new(instance: &str) -> Self {
+        Self(instance.to_string())
+    }
+
+    pub fn key(&self, name: &str) -> String {
+        if self.0.is_empty() {
+            format!("{}{}", PREFIX, name)
+        } else {
+            format!("{}{}.{}", PREFIX, self.0, name)
+        }
+    }
+}
+
+fn local_storage() -> Option<web_sys::Storage> {
+    web_sys::window()?.local_storage().ok()?
+}
+
+// Every key of ours, from every instance, with its raw stored text.
+pub fn local_entries() -> Vec<(String, String)> {
+    let storage = match local_storage() {
+        Some(storage) => storage,
+        None => return Vec::new(),
+    };
+
+    let length = storage.length().unwrap_or(0);
+    (0..length)
+        .filter_map(|i| storage.key(i).ok().flatten())
+        .filter(|key| key.starts_with(PREFIX))
+        .map(|key| {
+            let value = storage.get_item(&key).ok().flatten().unwrap_or_default();
+            (key, value)
+        })
+        .collect()
+}
+
+// Sizes are in bytes as the browser counts them: UTF-16, two bytes per unit.
+pub fn local_usage() -> Vec<(String, usize)> {
+    let mut usage: Vec<(String, usize)> = local_entries().into_iter()
+        .map(|(key, value)| {
+            let size = (key.encode_utf16().count() + value.encode_utf16().count()) * 2;
+            (key, size)
+        })
+        .collect();
+    usage.sort_by_key(|(_, size)| std::cmp::Reverse(*size));
+    usage
+}
+
+pub fn store(key: &str, value: &str) -> bool {
+    match local_storage().map(|storage| storage.set_item(key, value)) {
+        Some(Ok(())) => true,
+        Some(Err(e)) => {
+            log::warn!("Failed to store {}: {:?}", key, e);
+            false
+        }
+        None => false,
+    }
+}
+
+pub fn delete(key: &str) {
+    if let Some(storage) = local_storage() {
+        if let Err(e) = storage.remove_item(key) {
+            log::warn!("Failed to delete {}: {:?}", key, e);
+        }
+    }
+}
+
+pub async fn estimate() -> Option<Estimate> {
+    let promise = web_sys::window()?.navigator().storage().estimate().ok()?;
+    let result = JsFuture::from(promise).await.ok()?;
+    let field = |name: &str| Reflect::get(&result, &JsValue::from_str(name)).ok().and_then(|v| v.as_f64());
+    Some(Estimate {
+        usage: field("usage")?,
+        quota: field("quota")?,
+    })
+}
+
+// performance.memory is Chromium-only, hence the reflection.
+pub fn heap_used() -> Option<f64> {
+    let window: JsValue = web_sys::window()?.into();
+    let performance = Reflect::get(&window, &JsValue::from_str("performance")).ok()?;
+    let memory = Reflect::get(&performance, &JsValue::from_str("memory")).ok()?;
+    Reflect::get(&memory, &JsValue::from_str("usedJSHeapSize")).ok()?.as_f64()
+}
+
+fn format_size(bytes: f64) -> String {
+    if bytes >= 1024.0 * 1024.0 {
+        format!("{:.1} MB", bytes / 1024.0 / 1024.0)
+    } else {
+        format!("{:.1} KB", bytes / 1024.0)
+    }
+}
+
+pub fn view(usage: &[(String, usize)], estimate: Option<Estimate>, ondelete: Callback<String>) -> Html {
+    let total: usize = usage.iter().map(|(_, size)| size).sum();
+    let local_full = total as f64 >= LOCAL_STORAGE_LIMIT as f64 * WARN_RATIO;
+    let origin_full = estimate.map(|e| e.quota > 0.0 && e.usage / e.quota >= WARN_RATIO).unwrap_or(false);
+
+    html! {
+        <div class="storage">
+        <h3>{ "存储空间" }</h3>
+        <p>{ format!("本地存储：{} / {}", format_size(total as f64), format_size(LOCAL_STORAGE_LIMIT as f64)) }</p>
+        if let Some(estimate) = estimate {
+            <p>{ format!("浏览器估计：{} / {}", format_size(estimate.usage), format_size(estimate.quota)) }</p>
+        }
+        if let Some(heap) = heap_used() {
+            <p>{ format!("JS内存：{}", format_size(heap)) }</p>
+        }
+        if local_full || origin_full {
+            <p class="warning">{ "存储空间快满了，清理一下吧。" }</p>
+        }
+        <table class="stats">
+        { usage.iter().map(|(key, size)| {
+            let target = key.clone();
+            html! {
+                <tr>
+                <td>{ key.trim_start_matches(PREFIX) }</td>
+                <td>{ format_size(*size as f64) }</td>
+                <td><button onclick={ondelete.reform(move |_| target.clone())}>{ "删除" }</button></td>
+                </tr>
+            }
+        }).collect::<Html>() }
+        </table>
+        </div>
+    }
+}
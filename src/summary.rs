@@ -0,0 +1,73 @@
+use wasm_bindgen::{JsCast, JsValue};
+use web_sys::{CanvasRenderingContext2d, HtmlAnchorElement, HtmlCanvasElement};
+
+const WIDTH: u32 = 360;
+const HEIGHT: u32 = 276;
+
+pub struct Summary {
+    pub mode: &'static str,
+    pub score: u64,
+    pub highest_tile: String,
+    pub moves: u64,
+    pub seconds: u64,
+    pub best_move_score: u64,
+    pub most_merges_in_move: u64,
+    pub longest_combo: u64,
+}
+
+pub fn draw(canvas: &HtmlCanvasElement, summary: &Summary) {
+    canvas.set_width(WIDTH);
+    canvas.set_height(HEIGHT);
+    let context = match canvas.get_context("2d") {
+        Ok(Some(context)) => context,
+        _ => return,
+    };
+    let context = match context.dyn_into::<CanvasRenderingContext2d>() {
+        Ok(context) => context,
+        Err(_) => return,
+    };
+
+    context.set_fill_style(&JsValue::from_str("cornsilk"));
+    context.fill_rect(0.0, 0.0, WIDTH as f64, HEIGHT as f64);
+    context.set_fill_style(&JsValue::from_str("indianred"));
+    context.set_font("bold 28px sans-serif");
+    let _ = context.fill_text("yew-2048", 24.0, 48.0);
+
+    context.set_fill_style(&JsValue::from_str("dimgray"));
+    context.set_font("18px sans-serif");
+    let lines = [
+        format!("模式：{}", summary.mode),
+        format!("分数：{}", summary.score),
+        format!("最大方块：{}", summary.highest_tile),
+        format!("步数：{}", summary.moves),
+        format!("用时：{}:{:02}", summary.seconds / 60, summary.seconds % 60),
+        format!("一步最高{}分，最多合并{}次", summary.best_move_score, summary.most_merges_in_move),
+        format!("最长连击：{}步", summary.longest_combo),
+    ];
+    for (i, line) in lines.iter().enumerate() {
+        let _ = context.fill_text(line, 24.0, 86.0 + 28.0 * i as f64);
+    }
+}
+
+pub fn download(canvas: &HtmlCanvasElement) {
+    let url = match canvas.to_data_url_with_type("image/png") {
+        Ok(url) => url,
+        Err(e) => {
+            log::warn!("Failed to export summary: {:?}", e);
+            return;
+        }
+    };
+    save(&url, "yew-2048.png");
+}
+
+pub fn save(url: &str, name: &str) {
+    let anchor = web_sys::window()
+        .and_then(|w| w.document())
+        .and_then(|d| d.create_element("a").ok())
+        .and_then(|a| a.dyn_into::<HtmlAnchorElement>().ok());
+    if let Some(anchor) = anchor {
+        anchor.set_href(url);
+        anchor.set_download(name);
+        anchor.click();
+    }
+}
@@ -0,0 +1,97 @@
+use serde::{Deserialize, Serialize};
+
+use crate::board::Cell;
+
+const TIERS: u32 = 12;
+const EXPONENT_FROM: u64 = 1 << 13;
+// From eight digits on a number no longer fits a tile, whatever the display mode.
+const LONG_FROM: u128 = 10_000_000;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Palette {
+    #[default]
+    Classic,
+    Colorblind,
+    HighContrast,
+}
+
+impl Palette {
+    pub const ALL: [Palette; 3] = [Palette::Classic, Palette::Colorblind, Palette::HighContrast];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Palette::Classic => "经典",
+            Palette::Colorblind => "色弱友好",
+            Palette::HighContrast => "高对比度",
+        }
+    }
+
+    fn prefix(&self) -> &'static str {
+        match self {
+            Palette::Classic => "cell",
+            Palette::Colorblind => "cb",
+            Palette::HighContrast => "hc",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DisplayMode {
+    #[default]
+    Raw,
+    Doubled,
+    Exponent,
+}
+
+impl DisplayMode {
+    pub const ALL: [DisplayMode; 3] = [DisplayMode::Raw, DisplayMode::Doubled, DisplayMode::Exponent];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            DisplayMode::Raw => "原始值",
+            DisplayMode::Doubled => "2048式",
+            DisplayMode::Exponent => "大数用2^k",
+        }
+    }
+
+    // Tiles start at 1 and double, so 2048-style numbers are value × 2. Wide
+    // enough that doubling the biggest tile doesn't overflow.
+    pub fn value(&self, value: u64) -> u128 {
+        let value = value as u128;
+        if *self == DisplayMode::Raw { value } else { value * 2 }
+    }
+
+    // Inverse of `value`, for numbers typed in as they are shown.
+    pub fn raw(&self, shown: u64) -> u64 {
+        if *self == DisplayMode::Raw { shown } else { shown / 2 }
+    }
+
+    // Powers of two as 2^k; anything else that is too long in scientific notation.
+    pub fn format(&self, value: u64) -> String {
+        let shown = self.value(value);
+        if (*self == DisplayMode::Exponent && value >= EXPONENT_FROM) || shown >= LONG_FROM {
+            return if shown.is_power_of_two() {
+                format!("2^{}", shown.trailing_zeros())
+            } else {
+                format!("{:.2e}", shown as f64)
+            };
+        }
+        shown.to_string()
+    }
+}
+
+// Classic keys colours on the exact value; the alternate palettes go by
+// magnitude so they also cover Fibonacci and Threes tiles.
+pub fn tile_class(palette: Palette, cell: Cell) -> String {
+    match (palette, cell) {
+        (_, Cell::Wall) => "cell-wall".to_string(),
+        (Palette::Classic, Cell::Empty) => "cell-0".to_string(),
+        (Palette::Classic, Cell::Value(v)) => format!("cell-{}", v),
+        (p, Cell::Empty) => format!("cell-0 {}-0", p.prefix()),
+        (p, Cell::Value(v)) => format!("{} {}-{}", p.prefix(), p.prefix(), tier(v)),
+    }
+}
+
+fn tier(value: u64) -> u32 {
+    (64 - value.leading_zeros()).clamp(1, TIERS)
+}
@@ -0,0 +1,55 @@
+use yew::{html, Component, Context, Html, Properties};
+
+use crate::board::Position;
+
+// Everything a tile draws, as plain values so unchanged tiles compare equal
+// from one move to the next.
+#[derive(Properties, Clone, PartialEq)]
+pub struct TileProps {
+    pub position: Position,
+    pub class: String,
+    pub style: String,
+    pub text: String,
+    pub badge: Option<u32>,
+    // The popup's round and the points it shows.
+    pub popup: Option<(u64, u64)>,
+}
+
+// One tile over the board, keyed by its id, so a move only touches the tiles
+// it slid, merged or spawned.
+pub struct TileView;
+
+impl Component for TileView {
+    type Message = ();
+    type Properties = TileProps;
+
+    fn create(_ctx: &Context<Self>) -> Self {
+        Self
+    }
+
+    // Yew only gets here when the props differ, and every prop shows in the
+    // markup, so there's nothing to skip.
+    fn changed(&mut self, _ctx: &Context<Self>) -> bool {
+        true
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        let props = ctx.props();
+        let Position { row, column } = props.position;
+        html! {
+            <div class="tile" aria-hidden="true"
+                style={format!("transform: translate(calc(var(--tile-size) * {}), calc(var(--tile-size) * {}));", column, row)}>
+            <div class={props.class.clone()} style={props.style.clone()}>
+            { &props.text }
+            if let Some(merges) = props.badge {
+                <sup class="badge">{ merges }</sup>
+            }
+            // Keyed by round so a second merge on the same square restarts the float.
+            if let Some((round, value)) = props.popup {
+                <span key={round.to_string()} class="popup">{ format!("+{}", value) }</span>
+            }
+            </div>
+            </div>
+        }
+    }
+}
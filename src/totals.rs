@@ -0,0 +1,116 @@
+use std::collections::BTreeMap;
+
+use gloo::storage::{LocalStorage, Storage};
+use serde::{Deserialize, Serialize};
+use yew::{html, Callback, Html, MouseEvent};
+
+use crate::mode::GameMode;
+use crate::storage::Namespace;
+
+const TOTALS_KEY: &str = "totals";
+
+// How one finished game ended.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Outcome {
+    pub won: bool,
+    pub score: u64,
+    pub moves: u64,
+    pub seconds: u64,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ModeTotals {
+    pub games: u64,
+    pub wins: u64,
+    pub total_score: u64,
+    pub best_score: u64,
+    pub moves: u64,
+    pub seconds: u64,
+}
+
+impl ModeTotals {
+    fn add(&mut self, other: &ModeTotals) {
+        self.games += other.games;
+        self.wins += other.wins;
+        self.total_score += other.total_score;
+        self.best_score = self.best_score.max(other.best_score);
+        self.moves += other.moves;
+        self.seconds += other.seconds;
+    }
+
+    fn average_score(&self) -> u64 {
+        self.total_score.checked_div(self.games).unwrap_or(0)
+    }
+}
+
+// Every counted game ever finished, per mode.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Totals {
+    modes: BTreeMap<GameMode, ModeTotals>,
+    #[serde(skip)]
+    namespace: Namespace,
+}
+
+impl Totals {
+    pub fn load(namespace: &Namespace) -> Self {
+        let totals: Self = LocalStorage::get(namespace.key(TOTALS_KEY)).unwrap_or_default();
+        Self { namespace: namespace.clone(), ..totals }
+    }
+
+    fn save(&self) {
+        if let Err(e) = LocalStorage::set(self.namespace.key(TOTALS_KEY), self) {
+            log::warn!("Failed to save totals: {:?}", e);
+        }
+    }
+
+    // A won game that is played on finishes again; `before` is what its first
+    // finish already added, so it still counts once.
+    pub fn record(&mut self, mode: GameMode, outcome: Outcome, before: Option<Outcome>) {
+        let before = before.unwrap_or_default();
+        let totals = self.modes.entry(mode).or_default();
+        totals.add(&ModeTotals {
+            games: u64::from(before == Outcome::default()),
+            wins: u64::from(outcome.won && !before.won),
+            total_score: outcome.score.saturating_sub(before.score),
+            best_score: outcome.score,
+            moves: outcome.moves.saturating_sub(before.moves),
+            seconds: outcome.seconds.saturating_sub(before.seconds),
+        });
+        self.save();
+    }
+
+    pub fn reset(&mut self) {
+        self.modes.clear();
+        LocalStorage::delete(self.namespace.key(TOTALS_KEY));
+    }
+
+    pub fn view(&self, onreset: Callback<MouseEvent>) -> Html {
+        let mut all = ModeTotals::default();
+        for totals in self.modes.values() {
+            all.add(totals);
+        }
+        let row = |label: &str, totals: &ModeTotals| html! {
+            <tr>
+            <td>{ label }</td>
+            <td>{ totals.games }</td>
+            <td>{ totals.wins }</td>
+            <td>{ totals.average_score() }</td>
+            <td>{ totals.best_score }</td>
+            <td>{ totals.moves }</td>
+            <td>{ format!("{}:{:02}:{:02}", totals.seconds / 3600, totals.seconds / 60 % 60, totals.seconds % 60) }</td>
+            </tr>
+        };
+        html! {
+            <div class="totals">
+            <table class="stats">
+            <tr><th>{ "模式" }</th><th>{ "局数" }</th><th>{ "赢" }</th><th>{ "平均分" }</th><th>{ "最高分" }</th><th>{ "步数" }</th><th>{ "时长" }</th></tr>
+            { self.modes.iter().map(|(mode, totals)| row(mode.label(), totals)).collect::<Html>() }
+            { row("合计", &all) }
+            </table>
+            <button onclick={onreset}>{ "清空总统计" }</button>
+            </div>
+        }
+    }
+}
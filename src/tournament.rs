@@ -0,0 +1,249 @@
+use web_sys::{HtmlInputElement, HtmlSelectElement};
+use yew::{html, Callback, Event, Html, MouseEvent, TargetCast};
+
+use crate::ai;
+use crate::board::{Board, Direction, Event as BoardEvent};
+use crate::rng::Rng;
+use crate::rules::{Classic, Rules};
+use crate::settings::SolverChoice;
+
+const AI_DEPTH: u32 = 2;
+const MOVE_LIMIT: u64 = 3000;
+const DEFAULT_GAMES: u32 = 20;
+const CORNER_ORDER: [Direction; 4] = [Direction::Down, Direction::Left, Direction::Right, Direction::Up];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Policy {
+    Expectimax,
+    Shallow,
+    MonteCarlo,
+    Greedy,
+    Corner,
+    Random,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Side {
+    pub wins: u32,
+    pub games: u32,
+    pub total_score: u64,
+    pub best_tile: u64,
+}
+
+#[derive(Debug, Clone)]
+struct Run {
+    board: Board,
+    rng: Rng,
+    policy_rng: Rng,
+    score: u64,
+    moves: u64,
+    done: bool,
+}
+
+// Both policies play the same seed in each round, then the round is scored.
+#[derive(Debug, Clone)]
+pub struct Tournament {
+    pub policies: [Policy; 2],
+    pub games: u32,
+    seed: u64,
+    round: Option<[Run; 2]>,
+    played: u32,
+    pub sides: [Side; 2],
+    pub ties: u32,
+    running: bool,
+}
+
+impl Policy {
+    pub const ALL: [Policy; 6] = [Policy::Expectimax, Policy::Shallow, Policy::MonteCarlo, Policy::Greedy, Policy::Corner, Policy::Random];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Policy::Expectimax => "期望搜索（两层）",
+            Policy::Shallow => "期望搜索（一层）",
+            Policy::MonteCarlo => "随机模拟",
+            Policy::Greedy => "贪心（这步得分最多）",
+            Policy::Corner => "死守角落",
+            Policy::Random => "随便走",
+        }
+    }
+
+    fn choose(&self, board: &Board, rules: &dyn Rules, rng: &mut Rng) -> Option<Direction> {
+        match self {
+            Policy::Expectimax => ai::best_move(board, rules, AI_DEPTH),
+            Policy::Shallow => ai::best_move(board, rules, 1),
+            Policy::MonteCarlo => SolverChoice::MonteCarlo.solver().best_move(board, rules),
+            Policy::Greedy => ai::evaluate_moves(board, rules, 1).into_iter()
+                .max_by_key(|e| (e.points, e.empties))
+                .map(|e| e.direction),
+            Policy::Corner => {
+                let moving: Vec<Direction> = ai::evaluate_moves(board, rules, 1).iter().map(|e| e.direction).collect();
+                CORNER_ORDER.iter().copied().find(|d| moving.contains(d))
+            }
+            Policy::Random => {
+                let moving = ai::evaluate_moves(board, rules, 1);
+                (!moving.is_empty()).then(|| moving[rng.below(moving.len())].direction)
+            }
+        }
+    }
+}
+
+impl Side {
+    pub fn average(&self) -> u64 {
+        if self.games == 0 { 0 } else { self.total_score / self.games as u64 }
+    }
+}
+
+impl Run {
+    fn new(seed: u64) -> Self {
+        let mut run = Run {
+            board: Board::default(),
+            rng: Rng::new(seed),
+            policy_rng: Rng::new(!seed),
+            score: 0,
+            moves: 0,
+            done: false,
+        };
+        run.board.spawn(&Classic, &mut run.rng);
+        run.board.spawn(&Classic, &mut run.rng);
+        run
+    }
+
+    fn step(&mut self, policy: Policy) {
+        match policy.choose(&self.board, &Classic, &mut self.policy_rng) {
+            Some(direction) => {
+                self.score += self.board.slide(&Classic, direction).iter().map(BoardEvent::score).sum::<u64>();
+                self.board.spawn(&Classic, &mut self.rng);
+                self.moves += 1;
+                self.done = self.moves >= MOVE_LIMIT;
+            }
+            None => self.done = true,
+        }
+    }
+}
+
+impl Default for Tournament {
+    fn default() -> Self {
+        Self {
+            policies: [Policy::Expectimax, Policy::Greedy],
+            games: DEFAULT_GAMES,
+            seed: 0,
+            round: None,
+            played: 0,
+            sides: [Side::default(); 2],
+            ties: 0,
+            running: false,
+        }
+    }
+}
+
+impl Tournament {
+    pub fn start(&mut self, seed: u64) {
+        *self = Self { policies: self.policies, games: self.games.max(1), seed, running: true, ..Self::default() };
+    }
+
+    pub fn stop(&mut self) {
+        self.running = false;
+    }
+
+    pub fn running(&self) -> bool {
+        self.running
+    }
+
+    pub fn finished(&self) -> bool {
+        self.played >= self.games && self.played > 0
+    }
+
+    pub fn winner(&self) -> Option<usize> {
+        if !self.finished() {
+            return None;
+        }
+        let key = |side: &Side| (side.wins, side.total_score);
+        match key(&self.sides[0]).cmp(&key(&self.sides[1])) {
+            std::cmp::Ordering::Greater => Some(0),
+            std::cmp::Ordering::Less => Some(1),
+            std::cmp::Ordering::Equal => None,
+        }
+    }
+
+    // Plays moves until `deadline` (a Date::now timestamp) so the page stays responsive.
+    pub fn advance(&mut self, deadline: f64) {
+        while self.running && js_sys::Date::now() < deadline {
+            let seed = self.seed.wrapping_add(self.played as u64);
+            let round = self.round.get_or_insert_with(|| [Run::new(seed), Run::new(seed)]);
+            for (run, policy) in round.iter_mut().zip(self.policies) {
+                if !run.done {
+                    run.step(policy);
+                }
+            }
+            if round.iter().all(|run| run.done) {
+                if let Some(round) = self.round.take() {
+                    self.score(&round);
+                }
+            }
+        }
+    }
+
+    fn score(&mut self, round: &[Run; 2]) {
+        for (side, run) in self.sides.iter_mut().zip(round) {
+            side.games += 1;
+            side.total_score += run.score;
+            side.best_tile = side.best_tile.max(run.board.highest());
+        }
+        match round[0].score.cmp(&round[1].score) {
+            std::cmp::Ordering::Greater => self.sides[0].wins += 1,
+            std::cmp::Ordering::Less => self.sides[1].wins += 1,
+            std::cmp::Ordering::Equal => self.ties += 1,
+        }
+        self.played += 1;
+        if self.played >= self.games {
+            self.running = false;
+        }
+    }
+
+    pub fn view(&self, onpolicy: Callback<(usize, Policy)>, ongames: Callback<u32>, onstart: Callback<MouseEvent>, onstop: Callback<MouseEvent>) -> Html {
+        let winner = self.winner();
+        html! {
+            <div class="tournament">
+            <h3>{ "AI对战" }</h3>
+            <p>
+            { for (0..2).map(|side| {
+                let onchange = onpolicy.reform(move |event: Event| {
+                    let index: usize = event.target_unchecked_into::<HtmlSelectElement>().value().parse().unwrap_or(0);
+                    (side, Policy::ALL[index.min(Policy::ALL.len() - 1)])
+                });
+                html! {
+                    <select disabled={self.running} {onchange}>
+                    { Policy::ALL.iter().enumerate().map(|(i, policy)| html! {
+                        <option value={i.to_string()} selected={*policy == self.policies[side]}>{ policy.label() }</option>
+                    }).collect::<Html>() }
+                    </select>
+                }
+            }) }
+            { "局数" }
+            <input type="number" min="1" max="500" value={self.games.to_string()} disabled={self.running}
+                onchange={ongames.reform(|event: Event| event.target_unchecked_into::<HtmlInputElement>().value().parse().unwrap_or(DEFAULT_GAMES))} />
+            if self.running {
+                <button onclick={onstop}>{ "停" }</button>
+            } else {
+                <button onclick={onstart}>{ "开打" }</button>
+            }
+            </p>
+            <table class="stats">
+            <tr><th>{ "策略" }</th><th>{ "赢" }</th><th>{ "平均分" }</th><th>{ "最大方块" }</th></tr>
+            { for (0..2).map(|i| html! {
+                <tr class={if winner == Some(i) { "best" } else { "" }}>
+                <td>{ format!("{}{}", if winner == Some(i) { "🏆 " } else { "" }, self.policies[i].label()) }</td>
+                <td>{ self.sides[i].wins }</td>
+                <td>{ self.sides[i].average() }</td>
+                <td>{ self.sides[i].best_tile }</td>
+                </tr>
+            }) }
+            </table>
+            <p>{ format!("已打{}/{}局，平局{}", self.played, self.games, self.ties) }</p>
+            if self.finished() && winner.is_none() {
+                <p>{ "打平了" }</p>
+            }
+            </div>
+        }
+    }
+}
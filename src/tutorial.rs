@@ -0,0 +1,99 @@
+use gloo::storage::{LocalStorage, Storage};
+use yew::{html, Callback, Html, MouseEvent};
+
+use crate::board::{Board, Direction, Event};
+use crate::rules::Rules;
+use crate::settings::{Action, KeyBindings};
+use crate::storage::{self, Namespace};
+
+const TUTORIAL_KEY: &str = "tutorial-done";
+// Moves the tutorial picks for the player before letting go.
+const FORCED_MOVES: u8 = 2;
+
+// The first-launch walkthrough: a couple of moves the game picks, then a word
+// on merging. While a move is forced every other input is ignored.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Tutorial {
+    Move { made: u8, direction: Direction },
+    Merging,
+}
+
+// No key of this instance in storage, the done flag included, means nobody
+// has played here yet.
+pub fn first_launch(namespace: &Namespace) -> bool {
+    let prefix = namespace.key("");
+    !storage::local_entries().iter().any(|(key, _)| key.starts_with(&prefix))
+}
+
+pub fn finish(namespace: &Namespace) {
+    if let Err(e) = LocalStorage::set(namespace.key(TUTORIAL_KEY), true) {
+        log::warn!("Failed to save tutorial flag: {:?}", e);
+    }
+}
+
+// The direction that merges the most, or failing that the first one that
+// slides anything, so the forced move always does something.
+fn pick(board: &Board, rules: &dyn Rules) -> Direction {
+    Direction::ALL.iter()
+        .map(|direction| {
+            let mut after = *board;
+            let events = after.slide(rules, *direction);
+            let merges = events.iter().filter(|event| matches!(event, Event::Merge { .. })).count();
+            (*direction, merges, !events.is_empty())
+        })
+        .filter(|(_, _, moved)| *moved)
+        .max_by_key(|(_, merges, _)| *merges)
+        .map(|(direction, _, _)| direction)
+        .unwrap_or(Direction::Left)
+}
+
+impl Tutorial {
+    pub fn start(board: &Board, rules: &dyn Rules) -> Self {
+        Tutorial::Move { made: 0, direction: pick(board, rules) }
+    }
+
+    pub fn forced(&self) -> Option<Direction> {
+        match self {
+            Tutorial::Move { direction, .. } => Some(*direction),
+            Tutorial::Merging => None,
+        }
+    }
+
+    pub fn advance(&mut self, board: &Board, rules: &dyn Rules) {
+        if let Tutorial::Move { made, .. } = *self {
+            *self = match made + 1 < FORCED_MOVES {
+                true => Tutorial::Move { made: made + 1, direction: pick(board, rules) },
+                false => Tutorial::Merging,
+            };
+        }
+    }
+
+    pub fn view(&self, keys: &KeyBindings, onclose: Callback<MouseEvent>) -> Html {
+        let controls = Direction::ALL.iter().map(|direction| {
+            let action = Action::Move(*direction);
+            let class = if self.forced() == Some(*direction) { "highlight" } else { "" };
+            html! { <span {class}><kbd>{ keys.key(action) }</kbd>{ action.label() }</span> }
+        }).collect::<Html>();
+        html! {
+            <div class="overlay tutorial">
+            <h3>{ "第一次玩？" }</h3>
+            <p class="controls">{ controls }</p>
+            { match self {
+                Tutorial::Move { made: 0, direction } => html! {
+                    <p>{ format!("所有方块都往一个方向滑。先往{}试试：按{}，或者在棋盘上往{}划。", Action::Move(*direction).label(), keys.key(Action::Move(*direction)), Action::Move(*direction).label()) }</p>
+                },
+                Tutorial::Move { direction, .. } => html! {
+                    <p>{ format!("每走一步会冒出一个新方块。再往{}走一步。", Action::Move(*direction).label()) }</p>
+                },
+                Tutorial::Merging => html! {
+                    <>
+                    <p>{ "两个一样的方块撞到一起会合成一个，数字翻倍，分数加上新方块的数。" }</p>
+                    <p>{ "一直合下去，合出2048就赢了；棋盘满了又合不动就输了。" }</p>
+                    </>
+                },
+            } }
+            <button onclick={onclose}>{ if *self == Tutorial::Merging { "开始玩" } else { "跳过" } }</button>
+            </div>
+        }
+    }
+}
@@ -0,0 +1,33 @@
+// Caches everything the build produced, listed in precache.js by the
+// post-build hook, and serves it cache-first so the game loads with no
+// network. Anything else, like the global leaderboard, goes to the network.
+importScripts("precache.js");
+
+self.addEventListener("install", (event) => {
+  event.waitUntil(
+    caches.open(VERSION)
+      .then((cache) => cache.addAll(ASSETS))
+      .then(() => self.skipWaiting()),
+  );
+});
+
+// A new build brings a new VERSION; the old caches go once it takes over.
+self.addEventListener("activate", (event) => {
+  event.waitUntil(
+    caches.keys()
+      .then((keys) => Promise.all(keys.filter((key) => key !== VERSION).map((key) => caches.delete(key))))
+      .then(() => self.clients.claim()),
+  );
+});
+
+self.addEventListener("fetch", (event) => {
+  const url = new URL(event.request.url);
+  if (event.request.method !== "GET" || url.origin !== self.location.origin) {
+    return;
+  }
+  // ?race, ?puzzle= and the rest all load the same page.
+  event.respondWith(
+    caches.match(event.request, { ignoreSearch: true })
+      .then((cached) => cached || fetch(event.request)),
+  );
+});